@@ -1,5 +1,20 @@
-use clap::Parser;
+use crate::error::{FsgitError, Result};
+use crate::git::MatchOn;
+use clap::{Parser, ValueEnum};
+use glob::Pattern;
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// Order in which matched repositories are listed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SortOrder {
+    /// As found while scanning (matches are streamed)
+    #[default]
+    Found,
+    /// Most recently active first (commit, checkout or fetch)
+    Recent,
+}
 
 #[derive(Parser, Debug)]
 #[command(name = "fsgitwatch")]
@@ -10,13 +25,117 @@ Supports both SSH (git@github.com:owner/repo.git) and HTTPS (https://github.com/
 Checks all git remotes (origin, upstream, etc.) and intelligently prunes directory traversal.")]
 pub struct Cli {
     /// Repository pattern in owner/repo format (e.g., 'anthropics/claude-code')
+    /// Segments may use glob wildcards (e.g., 'anthropics/*', '*/claude-*')
+    /// Nested namespaces are written in full (e.g., 'group/subgroup/project')
+    /// A clone URL (e.g., 'git@github.com:owner/repo.git') is also accepted
+    /// Omit when using -e/--patterns-from; the first argument is then PATH
+    /// With --has-branch, --has-tag or --on-branch and no arguments, every repository
+    /// under the current directory is checked
     #[arg(value_name = "PATTERN")]
-    pub pattern: String,
+    pub pattern: Option<String>,
 
     /// Directory to search (defaults to current directory)
     #[arg(value_name = "PATH")]
     pub search_path: Option<PathBuf>,
 
+    /// Additional pattern to search for (repeatable, all searched in one pass)
+    #[arg(short = 'e', long = "pattern", value_name = "PATTERN")]
+    pub patterns: Vec<String>,
+
+    /// Read patterns from a file, one per line ('#' at line start or after whitespace starts a comment)
+    #[arg(long, value_name = "FILE")]
+    pub patterns_from: Option<PathBuf>,
+
+    /// Exclude repositories matching this pattern (repeatable, e.g. 'myorg/archive-*')
+    /// Always an owner/repo glob or clone URL: --regex and rename aliases don't apply
+    #[arg(long = "not", value_name = "PATTERN")]
+    pub exclude: Vec<String>,
+
+    /// Treat PATTERN as a regular expression matched against 'owner/repo' (or 'host/owner/repo')
+    #[arg(long)]
+    pub regex: bool,
+
+    /// Also report same-named remotes under other owners (a fork's origin) when another remote of the repo matches
+    #[arg(long)]
+    pub forks: bool,
+
+    /// List every linked worktree of matched repositories, even outside PATH
+    #[arg(long)]
+    pub worktrees: bool,
+
+    /// Keep scanning inside repositories' working trees to find nested clones
+    #[arg(long)]
+    pub nested: bool,
+
+    /// Inspect the initialized submodules of every repository found
+    #[arg(long)]
+    pub submodules: bool,
+
+    /// Find repositories that include PATTERN as a submodule, matching submodule URLs instead of remotes
+    #[arg(long)]
+    pub superprojects: bool,
+
+    /// Show branch, uncommitted changes, upstream divergence, stashes and in-progress operations
+    #[arg(long)]
+    pub status: bool,
+
+    /// Find repositories whose object database contains this commit (full or abbreviated SHA)
+    /// instead of matching remotes; the first argument is then PATH
+    #[arg(
+        long,
+        value_name = "SHA",
+        value_parser = parse_sha,
+        conflicts_with_all = ["patterns", "patterns_from", "superprojects", "same_root"]
+    )]
+    pub contains: Option<String>,
+
+    /// Find repositories sharing a root commit with the repository at REPO
+    /// instead of matching remotes; the first argument is then PATH
+    #[arg(long, value_name = "REPO", conflicts_with_all = ["patterns", "patterns_from", "superprojects"])]
+    pub same_root: Option<PathBuf>,
+
+    /// List repositories with no remotes, or only local-path remotes that no longer exist,
+    /// with their apparent size and last commit; the first argument is then PATH
+    #[arg(long, conflicts_with_all = ["patterns", "patterns_from", "superprojects", "contains", "same_root"])]
+    pub orphans: bool,
+
+    /// Only report repositories with a local branch named NAME (glob wildcards allowed)
+    #[arg(long, value_name = "NAME", value_parser = parse_ref_glob)]
+    pub has_branch: Option<Pattern>,
+
+    /// Only report repositories with a tag named NAME (glob wildcards allowed)
+    #[arg(long, value_name = "NAME", value_parser = parse_ref_glob)]
+    pub has_tag: Option<Pattern>,
+
+    /// Only report repositories whose HEAD is on a branch named NAME (glob wildcards allowed)
+    #[arg(long, value_name = "NAME", value_parser = parse_ref_glob)]
+    pub on_branch: Option<Pattern>,
+
+    /// Only report repositories with a commit, checkout or fetch within AGE (e.g. 2w, 30d, 6m)
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    pub active_since: Option<Duration>,
+
+    /// Only report repositories with no commit, checkout or fetch within AGE (e.g. 6m, 1y)
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    pub stale_for: Option<Duration>,
+
+    /// Order of the listed repositories: found or recent
+    #[arg(long, value_enum, value_name = "ORDER", default_value_t = SortOrder::Found)]
+    pub sort: SortOrder,
+
+    /// Report every repository's effective user.name and user.email and check them
+    /// against the [[identity]] rules in the config file; the first argument is then PATH
+    #[arg(long, conflicts_with_all = ["patterns", "patterns_from", "superprojects"])]
+    pub audit_identity: bool,
+
+    /// Which remote URLs to match: fetch, push or any
+    #[arg(long, value_enum, value_name = "WHICH", default_value_t = MatchOn::Any)]
+    pub match_on: MatchOn,
+
+    /// Config file (defaults to ~/.config/fsgitwatch/config.toml)
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
     /// Maximum number of concurrent scan tasks
     #[arg(short = 'j', long, default_value = "100")]
     pub max_concurrent: usize,
@@ -34,3 +153,245 @@ pub struct Cli {
     #[arg(long)]
     pub no_progress: bool,
 }
+
+impl Cli {
+    /// Collect patterns from the positional argument, -e and --patterns-from
+    /// Returns the patterns in order (duplicates removed) and the search path
+    pub fn resolve_patterns(&self) -> Result<(Vec<String>, Option<PathBuf>)> {
+        // Commit lookups, --orphans and --audit-identity take no pattern, so a lone positional is the path
+        if self.is_patternless() {
+            if self.search_path.is_some() {
+                return Err(FsgitError::UnexpectedPattern(self.pattern.clone().unwrap_or_default()));
+            }
+            return Ok((Vec::new(), self.pattern.as_ref().map(PathBuf::from)));
+        }
+
+        let mut patterns = self.patterns.clone();
+
+        if let Some(file) = &self.patterns_from {
+            let contents = std::fs::read_to_string(file)?;
+            patterns.extend(parse_pattern_list(&contents));
+        }
+
+        // Like grep -e: once patterns come from flags, a lone positional is the path
+        let mut search_path = self.search_path.clone();
+        if patterns.is_empty() {
+            patterns.extend(self.pattern.clone());
+        } else if let Some(positional) = &self.pattern {
+            if search_path.is_some() {
+                patterns.insert(0, positional.clone());
+            } else {
+                search_path = Some(PathBuf::from(positional));
+            }
+        }
+
+        if patterns.is_empty() {
+            return Err(FsgitError::MissingPattern);
+        }
+
+        let mut seen = HashSet::new();
+        patterns.retain(|pattern| seen.insert(pattern.clone()));
+
+        Ok((patterns, search_path))
+    }
+
+    /// Whether repositories are selected by commit, as orphans, for an identity audit
+    /// or by ref filters alone rather than matched by pattern
+    pub fn is_patternless(&self) -> bool {
+        self.contains.is_some() || self.same_root.is_some() || self.orphans || self.audit_identity || self.refs_only()
+    }
+
+    /// Ref filters given without any argument or pattern flag check every repository
+    /// under the current directory; a positional argument is always a pattern
+    fn refs_only(&self) -> bool {
+        let ref_filter = self.has_branch.is_some() || self.has_tag.is_some() || self.on_branch.is_some();
+        ref_filter
+            && !self.superprojects
+            && self.pattern.is_none()
+            && self.patterns.is_empty()
+            && self.patterns_from.is_none()
+    }
+}
+
+/// Parse a pattern list file: one pattern per line, blank lines and '#' comments ignored
+/// A '#' starts a comment only at the start of a line or after whitespace, so
+/// patterns such as `org/repo#\d+` keep theirs
+fn parse_pattern_list(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(|line| strip_comment(line).trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Cut a line at the first '#' that starts it or follows whitespace
+fn strip_comment(line: &str) -> &str {
+    let comment = line
+        .char_indices()
+        .find(|&(idx, c)| c == '#' && line[..idx].chars().next_back().is_none_or(char::is_whitespace));
+    comment.map_or(line, |(idx, _)| &line[..idx])
+}
+
+/// Compile a branch or tag name, which may contain glob wildcards
+fn parse_ref_glob(name: &str) -> std::result::Result<Pattern, String> {
+    Pattern::new(name).map_err(|e| format!("invalid name '{}': {}", name, e))
+}
+
+/// Accept a full or abbreviated (at least 4 digits) hexadecimal commit SHA
+fn parse_sha(sha: &str) -> std::result::Result<String, String> {
+    if (4..=40).contains(&sha.len()) && sha.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(sha.to_ascii_lowercase())
+    } else {
+        Err(format!("expected 4 to 40 hexadecimal digits, got '{}'", sha))
+    }
+}
+
+/// Parse an age such as "12h", "30d", "2w", "6m" (months) or "1y"
+fn parse_age(age: &str) -> std::result::Result<Duration, String> {
+    let split = age.find(|c: char| !c.is_ascii_digit()).unwrap_or(age.len());
+    let (count, unit) = age.split_at(split);
+    let count: u64 = count
+        .parse()
+        .map_err(|_| format!("expected a number followed by a unit, e.g. 30d, got '{}'", age))?;
+
+    const HOUR: u64 = 60 * 60;
+    const DAY: u64 = 24 * HOUR;
+    let unit = match unit {
+        "h" => HOUR,
+        "d" => DAY,
+        "w" => 7 * DAY,
+        "m" => 30 * DAY,
+        "y" => 365 * DAY,
+        _ => return Err(format!("unknown unit in '{}', use h, d, w, m or y", age)),
+    };
+
+    count
+        .checked_mul(unit)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("'{}' is too long an age", age))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_positional_pattern() {
+        let cli = Cli::parse_from(["fsgitwatch", "owner/repo", "/tmp"]);
+        let (patterns, path) = cli.resolve_patterns().unwrap();
+
+        assert_eq!(patterns, vec!["owner/repo"]);
+        assert_eq!(path, Some(PathBuf::from("/tmp")));
+    }
+
+    #[test]
+    fn test_repeated_patterns_take_positional_as_path() {
+        let cli = Cli::parse_from(["fsgitwatch", "-e", "a/b", "-e", "c/d", "/tmp"]);
+        let (patterns, path) = cli.resolve_patterns().unwrap();
+
+        assert_eq!(patterns, vec!["a/b", "c/d"]);
+        assert_eq!(path, Some(PathBuf::from("/tmp")));
+
+        let cli = Cli::parse_from(["fsgitwatch", "-e", "a/b", "c/d", "/tmp"]);
+        let (patterns, path) = cli.resolve_patterns().unwrap();
+
+        assert_eq!(patterns, vec!["c/d", "a/b"]);
+        assert_eq!(path, Some(PathBuf::from("/tmp")));
+    }
+
+    #[test]
+    fn test_repeated_exclusions() {
+        let cli = Cli::parse_from(["fsgitwatch", "myorg/*", "--not", "myorg/archive-*", "--not", "myorg/old"]);
+        let (patterns, _) = cli.resolve_patterns().unwrap();
+
+        assert_eq!(patterns, vec!["myorg/*"]);
+        assert_eq!(cli.exclude, vec!["myorg/archive-*", "myorg/old"]);
+    }
+
+    #[test]
+    fn test_commit_lookup_takes_path() {
+        let cli = Cli::parse_from(["fsgitwatch", "--contains", "1a2b3c4", "/tmp"]);
+        let (patterns, path) = cli.resolve_patterns().unwrap();
+
+        assert!(patterns.is_empty());
+        assert_eq!(path, Some(PathBuf::from("/tmp")));
+
+        let cli = Cli::parse_from(["fsgitwatch", "--same-root", "/src/ref", "owner/repo", "/tmp"]);
+        assert!(matches!(cli.resolve_patterns(), Err(FsgitError::UnexpectedPattern(_))));
+
+        assert!(Cli::try_parse_from(["fsgitwatch", "--contains", "1a2b3c4", "-e", "owner/repo"]).is_err());
+        assert!(Cli::try_parse_from(["fsgitwatch", "--contains", "main"]).is_err());
+    }
+
+    #[test]
+    fn test_orphans_take_path() {
+        let cli = Cli::parse_from(["fsgitwatch", "--orphans", "/tmp"]);
+        let (patterns, path) = cli.resolve_patterns().unwrap();
+
+        assert!(patterns.is_empty());
+        assert_eq!(path, Some(PathBuf::from("/tmp")));
+
+        let cli = Cli::parse_from(["fsgitwatch", "--orphans"]);
+        assert_eq!(cli.resolve_patterns().unwrap(), (Vec::new(), None));
+    }
+
+    #[test]
+    fn test_identity_audit_takes_path() {
+        let cli = Cli::parse_from(["fsgitwatch", "--audit-identity", "/tmp"]);
+        assert_eq!(cli.resolve_patterns().unwrap(), (Vec::new(), Some(PathBuf::from("/tmp"))));
+
+        let cli = Cli::parse_from(["fsgitwatch", "--audit-identity", "owner/repo", "/tmp"]);
+        assert!(matches!(cli.resolve_patterns(), Err(FsgitError::UnexpectedPattern(_))));
+
+        assert!(Cli::try_parse_from(["fsgitwatch", "--audit-identity", "-e", "owner/repo"]).is_err());
+    }
+
+    #[test]
+    fn test_ref_filters_without_pattern() {
+        let cli = Cli::parse_from(["fsgitwatch", "--on-branch", "main"]);
+        assert_eq!(cli.resolve_patterns().unwrap(), (Vec::new(), None));
+
+        // Never guessed from the filesystem: an existing directory is still a pattern
+        let cli = Cli::parse_from(["fsgitwatch", "--has-branch", "feature/x", "/tmp"]);
+        assert_eq!(cli.resolve_patterns().unwrap(), (vec!["/tmp".to_string()], None));
+
+        let cli = Cli::parse_from(["fsgitwatch", "--has-tag", "v2.1.0", "owner/repo"]);
+        assert_eq!(cli.resolve_patterns().unwrap(), (vec!["owner/repo".to_string()], None));
+
+        let cli = Cli::parse_from(["fsgitwatch", "--has-tag", "v2.1.0", "owner/repo", "/tmp"]);
+        let (patterns, path) = cli.resolve_patterns().unwrap();
+        assert_eq!(patterns, vec!["owner/repo"]);
+        assert_eq!(path, Some(PathBuf::from("/tmp")));
+    }
+
+    #[test]
+    fn test_missing_pattern() {
+        let cli = Cli::parse_from(["fsgitwatch"]);
+        assert!(matches!(cli.resolve_patterns(), Err(FsgitError::MissingPattern)));
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("12h"), Ok(Duration::from_secs(12 * 3600)));
+        assert_eq!(parse_age("2w"), Ok(Duration::from_secs(14 * 86400)));
+        assert_eq!(parse_age("6m"), Ok(Duration::from_secs(180 * 86400)));
+        assert_eq!(parse_age("1y"), Ok(Duration::from_secs(365 * 86400)));
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("30").is_err());
+        assert!(parse_age("3 days").is_err());
+        assert!(parse_age("99999999999999999y").is_err());
+    }
+
+    #[test]
+    fn test_parse_pattern_list() {
+        let contents = "# onboarding list\nanthropics/claude-code\n\n  rust-lang/cargo  # build tool\nanthropics/claude-code\n";
+        assert_eq!(
+            parse_pattern_list(contents),
+            vec!["anthropics/claude-code", "rust-lang/cargo", "anthropics/claude-code"]
+        );
+
+        let contents = "org/repo#\\d+\norg/issue#1\t# tracked\n\t# indented comment\n";
+        assert_eq!(parse_pattern_list(contents), vec!["org/repo#\\d+", "org/issue#1"]);
+    }
+}
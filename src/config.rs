@@ -0,0 +1,164 @@
+use crate::error::{FsgitError, Result};
+use crate::identity::IdentityRule;
+use crate::matcher::{GerritHosts, RepoAliases};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// User configuration, read from `~/.config/fsgitwatch/config.toml` by default
+///
+/// ```toml
+/// # hosts serving Gerrit, whose /a/ URL prefix is not part of the project
+/// gerrit_hosts = ["review.example.com"]
+///
+/// [aliases]
+/// # old name = current name, for renamed or transferred repositories
+/// "oldorg/oldname" = "neworg/newname"
+///
+/// # identity required by --audit-identity, per remote host (and owner)
+/// [[identity]]
+/// host = "gitlab.corp.com"
+/// email = "*@corp.com"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Old "owner/repo" -> current "owner/repo"
+    pub aliases: BTreeMap<String, String>,
+    /// Hosts whose HTTP(S) URLs carry Gerrit's authenticated-access `a/` prefix
+    pub gerrit_hosts: Vec<String>,
+    /// Commit identity rules, as written in the file
+    pub identity: Vec<IdentityRuleConfig>,
+}
+
+/// One `[[identity]]` table; every field may use glob wildcards
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IdentityRuleConfig {
+    /// Remote host the rule applies to
+    pub host: String,
+    /// Narrows the rule to remotes under matching owners
+    pub owner: Option<String>,
+    /// Required `user.email`
+    pub email: Option<String>,
+    /// Required `user.name`
+    pub name: Option<String>,
+}
+
+impl Config {
+    /// Default config file location
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("fsgitwatch").join("config.toml"))
+    }
+
+    /// Load the config from an explicit path, or from the default location
+    /// A missing default config is not an error; a missing explicit one is
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Self::default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents)
+                .map_err(|e| FsgitError::Config(format!("{}: {}", path.display(), e))),
+            Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(FsgitError::Config(format!("{}: {}", path.display(), e))),
+        }
+    }
+
+    /// Parse config file contents
+    pub fn parse(contents: &str) -> std::result::Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// Rename/transfer alias table for pattern matching
+    pub fn repo_aliases(&self) -> RepoAliases {
+        RepoAliases::new(&self.aliases)
+    }
+
+    /// Gerrit hosts, to drop the `a/` prefix from their URLs
+    pub fn gerrit_hosts(&self) -> GerritHosts {
+        GerritHosts::new(&self.gerrit_hosts)
+    }
+
+    /// Compiled commit identity rules for `--audit-identity`
+    pub fn identity_rules(&self) -> Result<Vec<IdentityRule>> {
+        self.identity
+            .iter()
+            .map(|rule| {
+                IdentityRule::new(&rule.host, rule.owner.as_deref(), rule.email.as_deref(), rule.name.as_deref())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aliases() {
+        let config = Config::parse(
+            r#"
+[aliases]
+"oldorg/oldname" = "neworg/newname"
+"group/sub/legacy" = "group/sub/current"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.aliases.len(), 2);
+        assert_eq!(config.aliases["oldorg/oldname"], "neworg/newname");
+    }
+
+    #[test]
+    fn test_parse_gerrit_hosts() {
+        let config = Config::parse("gerrit_hosts = [\"review.example.com\"]\n").unwrap();
+        assert_eq!(config.gerrit_hosts, vec!["review.example.com"]);
+        assert!(config
+            .gerrit_hosts()
+            .resolve_url("https://review.example.com/a/path/to/repo")
+            .is_some());
+    }
+
+    #[test]
+    fn test_parse_identity_rules() {
+        let config = Config::parse(
+            r#"
+[[identity]]
+host = "gitlab.corp.com"
+email = "*@corp.com"
+
+[[identity]]
+host = "github.com"
+owner = "corp-*"
+email = "*@corp.com"
+"#,
+        )
+        .unwrap();
+
+        let rules = config.identity_rules().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[1].to_string(), "github.com/corp-*");
+
+        assert!(Config::parse("[[identity]]\nemail = \"*@corp.com\"\n").is_err());
+        let invalid = Config::parse("[[identity]]\nhost = \"[\"\n").unwrap();
+        assert!(matches!(invalid.identity_rules(), Err(FsgitError::Config(_))));
+    }
+
+    #[test]
+    fn test_empty_and_invalid_config() {
+        assert!(Config::parse("").unwrap().aliases.is_empty());
+        assert!(Config::parse("[aliases]\n\"a/b\" = 1\n").is_err());
+        assert!(Config::parse("[unknown]\n").is_err());
+    }
+
+    #[test]
+    fn test_missing_explicit_config_is_an_error() {
+        assert!(Config::load(Some(Path::new("/nonexistent/fsgitwatch.toml"))).is_err());
+    }
+}
@@ -5,6 +5,21 @@ pub enum FsgitError {
     #[error("Invalid search pattern: {0}. Expected format: owner/repo")]
     InvalidPattern(String),
 
+    #[error(
+        "No search pattern given. Pass PATTERN, -e, --patterns-from, --contains, --same-root, --orphans, \
+         --audit-identity or a ref filter"
+    )]
+    MissingPattern,
+
+    #[error("Unexpected pattern '{0}': --contains, --same-root, --orphans and --audit-identity take no pattern")]
+    UnexpectedPattern(String),
+
+    #[error("Invalid regex pattern: {0}")]
+    InvalidRegex(String),
+
+    #[error("Config error: {0}")]
+    Config(String),
+
     #[error("Git error: {0}")]
     Git(#[from] git2::Error),
 
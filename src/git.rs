@@ -1,36 +1,910 @@
-use crate::error::Result;
-use git2::Repository;
-use std::path::Path;
+use crate::error::{FsgitError, Result};
+use crate::identity::Identity;
+use git2::{BranchType, Config, Repository, RepositoryState, StatusOptions};
+use glob::Pattern;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::task;
 
+/// Whether a URL is used to fetch from or push to a remote
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Fetch,
+    Push,
+    /// The same URL is used for both
+    Both,
+}
+
+impl Direction {
+    /// Whether git fetches from this URL
+    pub fn is_fetch(self) -> bool {
+        matches!(self, Direction::Fetch | Direction::Both)
+    }
+
+    /// Whether git pushes to this URL
+    pub fn is_push(self) -> bool {
+        matches!(self, Direction::Push | Direction::Both)
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Fetch => write!(f, "fetch"),
+            Direction::Push => write!(f, "push"),
+            Direction::Both => write!(f, "fetch+push"),
+        }
+    }
+}
+
+/// Which remote URLs a search is matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum MatchOn {
+    /// URLs git fetches from
+    Fetch,
+    /// URLs git pushes to
+    Push,
+    /// Every URL
+    #[default]
+    Any,
+}
+
+impl MatchOn {
+    /// Whether a URL used in `direction` is considered
+    pub fn accepts(self, direction: Direction) -> bool {
+        match self {
+            MatchOn::Fetch => direction.is_fetch(),
+            MatchOn::Push => direction.is_push(),
+            MatchOn::Any => true,
+        }
+    }
+}
+
+/// A remote URL as configured and as git will actually use it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteUrl {
+    pub name: String,
+    pub direction: Direction,
+    /// URL exactly as written in the config
+    pub raw_url: String,
+    /// Effective URL after `url.<base>.insteadOf` / `pushInsteadOf` rewrites
+    pub url: String,
+    /// Local repositories passed through to reach this URL, in order
+    /// Empty for the repository's own remotes
+    pub via: Vec<PathBuf>,
+    /// `remote.<name>.mirror` is set, as `git clone --mirror` does
+    pub mirror: bool,
+}
+
+/// A linked worktree (`git worktree add`) of a repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worktree {
+    pub path: PathBuf,
+    /// Checked-out branch; `None` when HEAD is detached or unreadable
+    pub branch: Option<String>,
+    /// The worktree directory no longer exists (`git worktree prune` would drop it)
+    pub missing: bool,
+}
+
+/// Where a linked worktree's `.git` file points
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorktreeLink {
+    /// Working tree of the main repository (its git dir when bare)
+    pub main: PathBuf,
+    /// Whether the main repository's git dir still exists
+    pub main_exists: bool,
+}
+
+/// A submodule URL declared by a repository in `.gitmodules` or `.git/config`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmoduleUrl {
+    pub name: String,
+    /// Path of the submodule inside the superproject's working tree
+    pub path: PathBuf,
+    /// Effective URL; relative URLs are resolved against the superproject's remote
+    pub url: String,
+    /// Commit pinned by the gitlink in the superproject's HEAD
+    pub pinned: Option<String>,
+}
+
+/// Working state of a repository, for `--status`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoStatus {
+    /// Checked-out branch; `None` when HEAD is detached
+    pub branch: Option<String>,
+    /// Commit HEAD points at; `None` before the first commit
+    pub head: Option<String>,
+    /// Tracked files with staged or unstaged changes
+    pub dirty: usize,
+    pub untracked: usize,
+    /// Upstream of the current branch, e.g. "origin/main"
+    pub upstream: Option<String>,
+    /// Commits the branch has that its upstream lacks
+    pub ahead: usize,
+    /// Commits the upstream has that the branch lacks
+    pub behind: usize,
+    pub stashes: usize,
+    /// Operation left in progress, e.g. "rebase", "merge" or "bisect"
+    pub operation: Option<String>,
+}
+
+/// When a repository was last worked on, as Unix timestamps in seconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Activity {
+    /// Committer time of the commit HEAD points at
+    pub head_commit: Option<i64>,
+    /// Most recent checkout recorded in the HEAD reflog
+    pub last_checkout: Option<i64>,
+    /// Modification time of FETCH_HEAD, written by every fetch and pull
+    pub last_fetch: Option<i64>,
+}
+
+impl Activity {
+    /// Latest of the recorded times; `None` when none is known
+    pub fn latest(&self) -> Option<i64> {
+        [self.head_commit, self.last_checkout, self.last_fetch]
+            .into_iter()
+            .flatten()
+            .max()
+    }
+}
+
+/// `--active-since` and `--stale-for` bounds on when a repository was last worked on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActivityWindow {
+    /// A commit, checkout or fetch happened within this long
+    pub active_since: Option<Duration>,
+    /// No commit, checkout or fetch happened within this long
+    pub stale_for: Option<Duration>,
+}
+
+impl ActivityWindow {
+    pub fn is_empty(&self) -> bool {
+        self.active_since.is_none() && self.stale_for.is_none()
+    }
+
+    /// Whether a repository last active at `latest` falls within the window at `now`
+    /// A repository with no recorded activity is stale, never active
+    pub fn contains(&self, latest: Option<i64>, now: i64) -> bool {
+        let cutoff = |age: Duration| now.saturating_sub(i64::try_from(age.as_secs()).unwrap_or(i64::MAX));
+
+        let recent_enough = self
+            .active_since
+            .is_none_or(|age| latest.is_some_and(|latest| latest >= cutoff(age)));
+        let stale_enough = self
+            .stale_for
+            .is_none_or(|age| latest.is_none_or(|latest| latest < cutoff(age)));
+
+        recent_enough && stale_enough
+    }
+}
+
+/// Branch and tag conditions a repository must meet; names may use glob wildcards
+#[derive(Debug, Clone, Default)]
+pub struct RefFilter {
+    /// A local branch with a matching name exists
+    pub has_branch: Option<Pattern>,
+    /// A tag with a matching name exists
+    pub has_tag: Option<Pattern>,
+    /// HEAD is on a branch with a matching name
+    pub on_branch: Option<Pattern>,
+}
+
+impl RefFilter {
+    pub fn is_empty(&self) -> bool {
+        self.has_branch.is_none() && self.has_tag.is_none() && self.on_branch.is_none()
+    }
+}
+
+/// Refs through which a repository met a [`RefFilter`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RefMatch {
+    /// Matching local branches, with `has_branch`
+    pub branches: Vec<String>,
+    /// Matching tags, with `has_tag`
+    pub tags: Vec<String>,
+    /// Checked-out branch, with `on_branch`
+    pub head: Option<String>,
+}
+
+/// `url.<base>.insteadOf` and `url.<base>.pushInsteadOf` rules from git config
+#[derive(Debug, Default)]
+struct UrlRewrites {
+    /// (prefix, base) pairs
+    instead_of: Vec<(String, String)>,
+    push_instead_of: Vec<(String, String)>,
+}
+
+impl UrlRewrites {
+    /// Load rules from a (merged system/global/repo) config
+    fn from_config(config: &Config) -> Result<Self> {
+        let mut rewrites = Self::default();
+
+        let mut entries = config.entries(Some(r"^url\..*\.(push)?insteadof$"))?;
+        while let Some(entry) = entries.next() {
+            let entry = entry?;
+            let (Some(name), Some(prefix)) = (entry.name(), entry.value()) else {
+                continue;
+            };
+
+            if let Some(base) = name.strip_prefix("url.").and_then(|n| n.strip_suffix(".pushinsteadof")) {
+                rewrites.push_instead_of.push((prefix.to_string(), base.to_string()));
+            } else if let Some(base) = name.strip_prefix("url.").and_then(|n| n.strip_suffix(".insteadof")) {
+                rewrites.instead_of.push((prefix.to_string(), base.to_string()));
+            }
+        }
+
+        Ok(rewrites)
+    }
+
+    /// Effective fetch URL: the longest matching insteadOf prefix wins
+    fn fetch_url(&self, url: &str) -> String {
+        rewrite(url, &self.instead_of).unwrap_or_else(|| url.to_string())
+    }
+
+    /// Effective push URL for a `url` entry of a remote without `pushurl`:
+    /// pushInsteadOf takes precedence, then insteadOf applies as for fetch
+    /// (an explicit `pushurl` only gets insteadOf, see `fetch_url`)
+    fn push_url(&self, url: &str) -> String {
+        rewrite(url, &self.push_instead_of).unwrap_or_else(|| self.fetch_url(url))
+    }
+}
+
+/// Replace the longest matching prefix with its base, as git does
+fn rewrite(url: &str, rules: &[(String, String)]) -> Option<String> {
+    rules
+        .iter()
+        .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(prefix, base)| format!("{}{}", base, &url[prefix.len()..]))
+}
+
+/// Local clones are followed at most this many hops deep
+const MAX_LOCAL_HOPS: usize = 8;
+
 /// Get all remote URLs from a git repository
-/// Returns a vector of (remote_name, url) tuples
-pub async fn get_remote_urls(repo_path: &Path) -> Result<Vec<(String, String)>> {
+/// Returns the effective fetch URL of every remote and its push URLs, merged
+/// into one `Direction::Both` entry when they are the same. Rewrites come from
+/// the repo, global and system config.
+/// Remotes pointing at local repositories are followed to the remotes of those
+/// repositories, recorded with the chain of local paths in `via`.
+pub async fn get_remote_urls(repo_path: &Path) -> Result<Vec<RemoteUrl>> {
+    let (_, urls) = open_repository(repo_path).await?;
+    Ok(urls)
+}
+
+/// Open a repository and read its remote URLs, as [`get_remote_urls`] does
+/// The repository is handed back so later steps can use it without reopening
+pub async fn open_repository(repo_path: &Path) -> Result<(Repository, Vec<RemoteUrl>)> {
     let path = repo_path.to_path_buf();
 
     // Wrap blocking git2 operations in spawn_blocking
     task::spawn_blocking(move || {
         let repo = Repository::open(&path)?;
-        let remotes = repo.remotes()?;
-
-        let mut urls = Vec::new();
-        for remote_name in remotes.iter() {
-            if let Some(name) = remote_name {
-                if let Ok(remote) = repo.find_remote(name) {
-                    if let Some(url) = remote.url() {
-                        urls.push((name.to_string(), url.to_string()));
-                    }
+        let mut urls = read_remotes(&repo)?;
+
+        let mut visited = HashSet::new();
+        visited.extend(path.canonicalize());
+        urls.extend(follow_local_remotes(&repo, &urls, &[], &mut visited));
+
+        Ok((repo, urls))
+    })
+    .await?
+}
+
+/// Run blocking git2 calls against an open repository on the blocking pool,
+/// handing the repository back for the next step
+pub async fn run_blocking<T, F>(repo: Repository, f: F) -> Result<(Repository, T)>
+where
+    T: Send + 'static,
+    F: FnOnce(&Repository) -> T + Send + 'static,
+{
+    Ok(task::spawn_blocking(move || {
+        let value = f(&repo);
+        (repo, value)
+    })
+    .await?)
+}
+
+/// What [`inspect`] reads from a repository besides its linked worktrees
+#[derive(Debug, Clone, Default)]
+pub struct Inspection {
+    /// Branch and tag conditions the repository must meet
+    pub ref_filter: Arc<RefFilter>,
+    /// Record commit, checkout and fetch times
+    pub activity: bool,
+    /// Bounds on the latest of those times
+    pub window: ActivityWindow,
+    /// Read the working state
+    pub status: bool,
+    /// Read the commit identity
+    pub identity: bool,
+}
+
+/// What [`inspect`] read; a read that failed is left empty and listed in `failures`
+#[derive(Debug, Default)]
+pub struct Details {
+    /// The ref filter or activity window ruled the repository out; nothing after them was read
+    pub rejected: bool,
+    pub refs: Option<RefMatch>,
+    pub activity: Option<Activity>,
+    pub worktrees: Vec<Worktree>,
+    pub status: Option<RepoStatus>,
+    pub identity: Option<Identity>,
+    /// What could not be read and why, e.g. ("status", error)
+    pub failures: Vec<(&'static str, FsgitError)>,
+}
+
+/// Read everything `inspection` asks for from an open repository in one blocking pass
+/// Ref and activity conditions come first, so a repository they rule out costs nothing more
+pub async fn inspect(mut repo: Repository, inspection: Inspection) -> Result<Details> {
+    Ok(task::spawn_blocking(move || {
+        let mut details = Details::default();
+
+        if !inspection.ref_filter.is_empty() {
+            match match_refs(&repo, &inspection.ref_filter) {
+                Ok(Some(refs)) => details.refs = Some(refs),
+                Ok(None) => details.rejected = true,
+                Err(e) => {
+                    details.failures.push(("refs", e));
+                    details.rejected = true;
                 }
             }
+            if details.rejected {
+                return details;
+            }
         }
 
-        Ok(urls)
+        if inspection.activity || !inspection.window.is_empty() {
+            match get_activity(&repo) {
+                Ok(activity) => details.activity = Some(activity),
+                Err(e) => details.failures.push(("activity", e)),
+            }
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since_epoch| since_epoch.as_secs() as i64);
+            if !inspection.window.contains(details.activity.and_then(|activity| activity.latest()), now) {
+                details.rejected = true;
+                return details;
+            }
+        }
+
+        match list_worktrees(&repo) {
+            Ok(worktrees) => details.worktrees = worktrees,
+            Err(e) => details.failures.push(("worktrees", e)),
+        }
+
+        if inspection.status {
+            match get_status(&mut repo) {
+                Ok(status) => details.status = Some(status),
+                Err(e) => details.failures.push(("status", e)),
+            }
+        }
+
+        if inspection.identity {
+            match get_identity(&repo) {
+                Ok(identity) => details.identity = Some(identity),
+                Err(e) => details.failures.push(("identity", e)),
+            }
+        }
+
+        details
+    })
+    .await?)
+}
+
+/// Read the configured remotes of an opened repository
+/// Git fetches from the first `remote.<name>.url` and pushes to every
+/// `remote.<name>.pushurl`, or to every `url` when there is no `pushurl`
+fn read_remotes(repo: &Repository) -> Result<Vec<RemoteUrl>> {
+    let config = repo.config()?.snapshot()?;
+    let rewrites = UrlRewrites::from_config(&config)?;
+    let remotes = repo.remotes()?;
+
+    let mut urls = Vec::new();
+    for name in remotes.iter().flatten() {
+        let raw_urls = config_values(&config, &format!("remote.{}.url", name))?;
+        let raw_push_urls = config_values(&config, &format!("remote.{}.pushurl", name))?;
+        let mirror = config.get_bool(&format!("remote.{}.mirror", name)).unwrap_or(false);
+
+        let mut entries: Vec<RemoteUrl> = Vec::new();
+        if let Some(raw_url) = raw_urls.first() {
+            entries.push(RemoteUrl {
+                name: name.to_string(),
+                direction: Direction::Fetch,
+                raw_url: raw_url.clone(),
+                url: rewrites.fetch_url(raw_url),
+                via: Vec::new(),
+                mirror,
+            });
+        }
+
+        let push_urls: Vec<(String, String)> = if raw_push_urls.is_empty() {
+            raw_urls.iter().map(|raw| (raw.clone(), rewrites.push_url(raw))).collect()
+        } else {
+            raw_push_urls.iter().map(|raw| (raw.clone(), rewrites.fetch_url(raw))).collect()
+        };
+
+        for (raw_url, url) in push_urls {
+            // Only list a push URL separately when it differs from the fetch URL
+            if let Some(fetch) = entries.iter_mut().find(|e| e.direction == Direction::Fetch && e.url == url) {
+                fetch.direction = Direction::Both;
+                continue;
+            }
+            entries.push(RemoteUrl {
+                name: name.to_string(),
+                direction: Direction::Push,
+                raw_url,
+                url,
+                via: Vec::new(),
+                mirror,
+            });
+        }
+
+        urls.extend(entries);
+    }
+
+    Ok(urls)
+}
+
+/// Every value of a multi-valued config key, in config order
+fn config_values(config: &Config, name: &str) -> Result<Vec<String>> {
+    let mut values = Vec::new();
+    let mut entries = config.multivar(name, None)?;
+    while let Some(entry) = entries.next() {
+        values.extend(entry?.value().map(str::to_string));
+    }
+    Ok(values)
+}
+
+/// Follow fetch remotes that point at local repositories, transitively
+/// Followed URLs keep the name of the remote they were reached from;
+/// `visited` holds canonical paths already seen, so cycles end quietly
+fn follow_local_remotes(
+    repo: &Repository,
+    remotes: &[RemoteUrl],
+    via: &[PathBuf],
+    visited: &mut HashSet<PathBuf>,
+) -> Vec<RemoteUrl> {
+    if via.len() >= MAX_LOCAL_HOPS {
+        return Vec::new();
+    }
+
+    // Relative remotes resolve against the working tree (or the git dir when bare)
+    let base = repo.workdir().unwrap_or_else(|| repo.path());
+
+    let mut followed = Vec::new();
+    for remote in remotes.iter().filter(|r| r.direction.is_fetch()) {
+        let Some(target) = local_path(&remote.url, base) else {
+            continue;
+        };
+        let Ok(target) = target.canonicalize() else {
+            continue;
+        };
+        if !visited.insert(target.clone()) {
+            continue;
+        }
+        let Ok(target_repo) = Repository::open(&target) else {
+            continue;
+        };
+        let Ok(target_remotes) = read_remotes(&target_repo) else {
+            continue;
+        };
+
+        let mut chain = via.to_vec();
+        chain.push(target);
+
+        let nested = follow_local_remotes(&target_repo, &target_remotes, &chain, visited);
+        followed.extend(target_remotes.into_iter().chain(nested).map(|mut url| {
+            url.name = remote.name.clone();
+            if url.via.is_empty() {
+                url.via = chain.clone();
+            }
+            url
+        }));
+    }
+
+    followed
+}
+
+/// Detect a linked worktree from its `.git` file
+/// The file reads `gitdir: <main git dir>/worktrees/<name>`; anything else,
+/// such as a submodule's `gitdir: ../.git/modules/<name>`, is not a worktree
+pub async fn linked_worktree(path: &Path) -> Option<WorktreeLink> {
+    let contents = tokio::fs::read_to_string(path.join(".git")).await.ok()?;
+    let gitdir = contents.lines().find_map(|line| line.strip_prefix("gitdir:"))?.trim();
+    let gitdir = path.join(gitdir);
+
+    let worktrees = gitdir.parent()?;
+    if worktrees.file_name()? != "worktrees" {
+        return None;
+    }
+
+    let common_dir = worktrees.parent()?;
+    let main = if common_dir.file_name()? == ".git" {
+        common_dir.parent()?.to_path_buf()
+    } else {
+        common_dir.to_path_buf()
+    };
+
+    Some(WorktreeLink {
+        main,
+        main_exists: tokio::fs::metadata(&gitdir).await.is_ok(),
+    })
+}
+
+/// Read the working state of a repository: branch, changes, upstream
+/// divergence, stashes and any operation in progress
+pub fn get_status(repo: &mut Repository) -> Result<RepoStatus> {
+    let mut status = RepoStatus::default();
+
+    if let Ok(head) = repo.head() {
+        status.head = head.target().map(|oid| oid.to_string());
+        if head.is_branch() {
+            status.branch = head.shorthand().map(str::to_string);
+        }
+    } else if let Ok(head) = repo.find_reference("HEAD") {
+        // Unborn branch: HEAD names a branch with no commit yet
+        status.branch = head
+            .symbolic_target()
+            .and_then(|target| target.strip_prefix("refs/heads/"))
+            .map(str::to_string);
+    }
+
+    // Upstream divergence
+    if let Some(branch) = &status.branch {
+        if let Ok(upstream) = repo.find_branch(branch, BranchType::Local).and_then(|b| b.upstream()) {
+            status.upstream = upstream.name().ok().flatten().map(str::to_string);
+            if let (Some(local), Some(remote)) = (
+                status.head.as_deref().and_then(|h| git2::Oid::from_str(h).ok()),
+                upstream.get().target(),
+            ) {
+                (status.ahead, status.behind) = repo.graph_ahead_behind(local, remote)?;
+            }
+        }
+    }
+
+    // Bare repositories have no working tree to be dirty
+    if !repo.is_bare() {
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(false);
+        for entry in repo.statuses(Some(&mut options))?.iter() {
+            if entry.status().is_wt_new() {
+                status.untracked += 1;
+            } else if !entry.status().is_ignored() {
+                status.dirty += 1;
+            }
+        }
+    }
+
+    status.operation = match repo.state() {
+        RepositoryState::Clean => None,
+        RepositoryState::Merge => Some("merge"),
+        RepositoryState::Revert | RepositoryState::RevertSequence => Some("revert"),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some("cherry-pick"),
+        RepositoryState::Bisect => Some("bisect"),
+        RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge => {
+            Some("rebase")
+        }
+        RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => Some("am"),
+    }
+    .map(str::to_string);
+
+    repo.stash_foreach(|_, _, _| {
+        status.stashes += 1;
+        true
+    })?;
+
+    Ok(status)
+}
+
+/// Read when a repository last had a commit at HEAD, a checkout and a fetch
+pub fn get_activity(repo: &Repository) -> Result<Activity> {
+    let head_commit = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .ok()
+        .map(|commit| commit.time().seconds());
+
+    // Reflog entries are newest first; unborn repositories have none
+    let last_checkout = repo.reflog("HEAD").ok().and_then(|reflog| {
+        reflog
+            .iter()
+            .find(|entry| entry.message().is_some_and(|message| message.starts_with("checkout:")))
+            .map(|entry| entry.committer().when().seconds())
+    });
+
+    let last_fetch = std::fs::metadata(repo.path().join("FETCH_HEAD"))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|since_epoch| since_epoch.as_secs() as i64);
+
+    Ok(Activity {
+        head_commit,
+        last_checkout,
+        last_fetch,
+    })
+}
+
+/// Check a repository's branches, tags and HEAD against every condition of a filter
+/// Returns the refs that matched, or `None` when any condition fails
+pub fn match_refs(repo: &Repository, filter: &RefFilter) -> Result<Option<RefMatch>> {
+    let mut refs = RefMatch::default();
+
+    if let Some(pattern) = &filter.on_branch {
+        let head = repo.find_reference("HEAD")?;
+        // Unborn branches count: HEAD names them before the first commit
+        let branch = head.symbolic_target().and_then(|target| target.strip_prefix("refs/heads/"));
+        match branch {
+            Some(branch) if pattern.matches(branch) => refs.head = Some(branch.to_string()),
+            _ => return Ok(None),
+        }
+    }
+
+    if let Some(pattern) = &filter.has_branch {
+        for branch in repo.branches(Some(BranchType::Local))? {
+            if let Some(name) = branch?.0.name()? {
+                if pattern.matches(name) {
+                    refs.branches.push(name.to_string());
+                }
+            }
+        }
+        if refs.branches.is_empty() {
+            return Ok(None);
+        }
+    }
+
+    if let Some(pattern) = &filter.has_tag {
+        refs.tags = repo
+            .tag_names(None)?
+            .iter()
+            .flatten()
+            .filter(|tag| pattern.matches(tag))
+            .map(str::to_string)
+            .collect();
+        if refs.tags.is_empty() {
+            return Ok(None);
+        }
+    }
+
+    Ok(Some(refs))
+}
+
+/// Read the `user.name` and `user.email` commits in a repository would be made with,
+/// from its merged config; `includeIf` sections (`gitdir:`, `onbranch:`) are resolved
+pub fn get_identity(repo: &Repository) -> Result<Identity> {
+    let config = repo.config()?;
+    Ok(Identity {
+        name: config.get_string("user.name").ok(),
+        email: config.get_string("user.email").ok(),
+    })
+}
+
+/// Look up a commit by full or abbreviated SHA in a repository's object database
+/// Returns its full SHA, or `None` when the repository doesn't have it
+pub fn find_commit(repo: &Repository, sha: &str) -> Result<Option<String>> {
+    match repo.find_commit_by_prefix(sha) {
+        Ok(commit) => Ok(Some(commit.id().to_string())),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Parentless commits reachable from HEAD and the local branches of a repository
+pub async fn root_commits(repo_path: &Path) -> Result<Vec<String>> {
+    let path = repo_path.to_path_buf();
+
+    task::spawn_blocking(move || {
+        let repo = Repository::open(&path)?;
+        let mut revwalk = repo.revwalk()?;
+        // An unborn HEAD has nothing to walk
+        let _ = revwalk.push_head();
+        revwalk.push_glob("refs/heads")?;
+
+        let mut roots = Vec::new();
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            if commit.parent_count() == 0 {
+                roots.push(commit.id().to_string());
+            }
+        }
+        Ok(roots)
     })
     .await?
 }
 
+/// Paths of the submodules declared in a repository's `.gitmodules`,
+/// joined to the repository path; empty when there is none
+pub async fn submodule_paths(repo_path: &Path) -> Vec<PathBuf> {
+    let path = repo_path.to_path_buf();
+
+    task::spawn_blocking(move || {
+        let gitmodules = path.join(".gitmodules");
+        if !gitmodules.is_file() {
+            return Vec::new();
+        }
+        let Ok(config) = Config::open(&gitmodules) else {
+            return Vec::new();
+        };
+
+        let mut paths = Vec::new();
+        if let Ok(mut entries) = config.entries(Some(r"^submodule\..*\.path$")) {
+            while let Some(Ok(entry)) = entries.next() {
+                paths.extend(entry.value().map(|value| path.join(value.trim_end_matches('/'))));
+            }
+        }
+        paths
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Submodule URLs a repository declares, from `.gitmodules` and from
+/// `submodule.<name>.url` in its config (set by `git submodule init`)
+/// A submodule whose two URLs differ is listed once per URL
+pub fn get_submodule_urls(repo: &Repository) -> Result<Vec<SubmoduleUrl>> {
+    let Some(workdir) = repo.workdir() else {
+        return Ok(Vec::new());
+    };
+
+    let config = repo.config()?.snapshot()?;
+    let rewrites = UrlRewrites::from_config(&config)?;
+
+    // name -> (path, URLs)
+    let mut declared: BTreeMap<String, (Option<String>, Vec<String>)> = BTreeMap::new();
+    let gitmodules = workdir.join(".gitmodules");
+    if gitmodules.is_file() {
+        let modules = Config::open(&gitmodules)?;
+        let mut entries = modules.entries(Some(r"^submodule\..*\.(path|url)$"))?;
+        while let Some(entry) = entries.next() {
+            let entry = entry?;
+            let (Some((name, key)), Some(value)) = (entry.name().and_then(submodule_key), entry.value()) else {
+                continue;
+            };
+            let (module_path, urls) = declared.entry(name.to_string()).or_default();
+            match key {
+                "path" => *module_path = Some(value.to_string()),
+                _ => urls.push(value.to_string()),
+            }
+        }
+    }
+
+    let mut entries = config.entries(Some(r"^submodule\..*\.url$"))?;
+    while let Some(entry) = entries.next() {
+        let entry = entry?;
+        if let (Some((name, _)), Some(value)) = (entry.name().and_then(submodule_key), entry.value()) {
+            declared.entry(name.to_string()).or_default().1.push(value.to_string());
+        }
+    }
+
+    // Relative URLs ("../lib.git") are relative to the superproject's remote
+    let base = config
+        .get_string("remote.origin.url")
+        .ok()
+        .or_else(|| {
+            let remotes = repo.remotes().ok()?;
+            let name = remotes.iter().flatten().next()?;
+            config.get_string(&format!("remote.{}.url", name)).ok()
+        })
+        .map(|url| rewrites.fetch_url(&url));
+
+    let head_tree = repo.head().and_then(|head| head.peel_to_tree()).ok();
+
+    let mut submodules = Vec::new();
+    for (name, (module_path, urls)) in declared {
+        let module_path = PathBuf::from(module_path.unwrap_or_else(|| name.clone()));
+
+        // The gitlink is a tree entry with the commit file mode
+        let pinned = head_tree
+            .as_ref()
+            .and_then(|tree| tree.get_path(&module_path).ok())
+            .filter(|entry| entry.filemode() == 0o160000)
+            .map(|entry| entry.id().to_string());
+
+        let mut seen = HashSet::new();
+        for url in urls {
+            let url = match &base {
+                Some(base) if url.starts_with("./") || url.starts_with("../") => resolve_relative_url(base, &url),
+                _ => url,
+            };
+            let url = rewrites.fetch_url(&url);
+            if seen.insert(url.clone()) {
+                submodules.push(SubmoduleUrl {
+                    name: name.clone(),
+                    path: module_path.clone(),
+                    url,
+                    pinned: pinned.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(submodules)
+}
+
+/// Split "submodule.<name>.<key>" into (name, key); names may contain dots
+fn submodule_key(entry_name: &str) -> Option<(&str, &str)> {
+    entry_name.strip_prefix("submodule.")?.rsplit_once('.')
+}
+
+/// Resolve a relative submodule URL against the superproject's remote URL, as git does:
+/// each "../" drops one path component of the remote URL, "./" drops none
+fn resolve_relative_url(base: &str, url: &str) -> String {
+    let mut base = base.trim_end_matches('/').to_string();
+    let mut separator = '/';
+    let mut rest = url;
+
+    loop {
+        if let Some(stripped) = rest.strip_prefix("./") {
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix("../") {
+            rest = stripped;
+            match base.rfind(['/', ':']) {
+                Some(idx) => {
+                    separator = if base[idx..].starts_with(':') { ':' } else { '/' };
+                    base.truncate(idx);
+                }
+                None => base.clear(),
+            }
+        } else {
+            break;
+        }
+    }
+
+    format!("{}{}{}", base, separator, rest)
+}
+
+/// List the linked worktrees of a repository, wherever they live
+pub fn list_worktrees(repo: &Repository) -> Result<Vec<Worktree>> {
+    let mut worktrees = Vec::new();
+    for name in repo.worktrees()?.iter().flatten() {
+        let Ok(worktree) = repo.find_worktree(name) else {
+            continue;
+        };
+
+        let branch = Repository::open_from_worktree(&worktree)
+            .ok()
+            .and_then(|wt_repo| {
+                let head = wt_repo.head().ok()?;
+                head.is_branch().then(|| head.shorthand().map(str::to_string)).flatten()
+            });
+
+        worktrees.push(Worktree {
+            path: worktree.path().to_path_buf(),
+            branch,
+            missing: !worktree.path().exists(),
+        });
+    }
+
+    Ok(worktrees)
+}
+
+/// Filesystem path of a local remote URL, if it is one
+/// Git treats a URL as local when it is `file://` or has no scheme and no
+/// scp-like `host:` prefix; relative paths resolve against `base`
+pub fn local_path(url: &str, base: &Path) -> Option<PathBuf> {
+    let path = if let Some(path) = url.strip_prefix("file://") {
+        PathBuf::from(path)
+    } else if url.contains("://") {
+        return None;
+    } else if url.split('/').next().is_some_and(|first| first.contains(':')) {
+        // scp-like host:path
+        return None;
+    } else if let Some(rest) = url.strip_prefix("~/") {
+        dirs::home_dir()?.join(rest)
+    } else {
+        PathBuf::from(url)
+    };
+
+    Some(if path.is_relative() { base.join(path) } else { path })
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use std::process::Command;
     use tempfile::TempDir;
@@ -40,30 +914,55 @@ mod tests {
         let repo_path = temp_dir.path();
 
         // Initialize git repo
-        Command::new("git")
-            .args(&["init"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
+        git(repo_path, &["init"]);
 
         // Add remote
-        Command::new("git")
-            .args(&["remote", "add", "origin", remote_url])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
+        git(repo_path, &["remote", "add", "origin", remote_url]);
 
         temp_dir
     }
 
+    /// A git command run in `repo_path` with a throwaway commit identity
+    fn git_command(repo_path: &Path, args: &[&str]) -> Command {
+        let mut command = Command::new("git");
+        command
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .current_dir(repo_path);
+        command
+    }
+
+    /// Run git in `repo_path` and return its trimmed stdout
+    pub(crate) fn git(repo_path: &Path, args: &[&str]) -> String {
+        let output = git_command(repo_path, args).output().unwrap();
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    /// Open a test repository
+    fn open(repo_path: &Path) -> Repository {
+        Repository::open(repo_path).unwrap()
+    }
+
+    /// A fetch+push remote as read from a repository's config
+    pub(crate) fn remote(name: &str, url: &str) -> RemoteUrl {
+        RemoteUrl {
+            name: name.to_string(),
+            direction: Direction::Both,
+            raw_url: url.to_string(),
+            url: url.to_string(),
+            via: Vec::new(),
+            mirror: false,
+        }
+    }
+
     #[tokio::test]
     async fn test_get_remote_urls() {
         let temp_dir = create_test_repo_with_remote("https://github.com/test/repo.git").await;
         let remotes = get_remote_urls(temp_dir.path()).await.unwrap();
 
         assert_eq!(remotes.len(), 1);
-        assert_eq!(remotes[0].0, "origin");
-        assert_eq!(remotes[0].1, "https://github.com/test/repo.git");
+        assert_eq!(remotes[0].name, "origin");
+        assert_eq!(remotes[0].url, "https://github.com/test/repo.git");
     }
 
     #[tokio::test]
@@ -72,29 +971,407 @@ mod tests {
         let repo_path = temp_dir.path();
 
         // Initialize git repo
-        Command::new("git")
-            .args(&["init"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
+        git(repo_path, &["init"]);
 
         // Add multiple remotes
-        Command::new("git")
-            .args(&["remote", "add", "origin", "https://github.com/test/repo.git"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
+        git(repo_path, &["remote", "add", "origin", "https://github.com/test/repo.git"]);
 
-        Command::new("git")
-            .args(&["remote", "add", "upstream", "git@github.com:upstream/repo.git"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
+        git(repo_path, &["remote", "add", "upstream", "git@github.com:upstream/repo.git"]);
+
+        let remotes = get_remote_urls(repo_path).await.unwrap();
+
+        assert_eq!(remotes.len(), 2);
+        assert!(remotes.iter().any(|remote| remote.name == "origin"));
+        assert!(remotes.iter().any(|remote| remote.name == "upstream"));
+    }
+
+    #[tokio::test]
+    async fn test_insteadof_rewrites() {
+        let temp_dir = create_test_repo_with_remote("gh:org/repo").await;
+        let repo_path = temp_dir.path();
+
+        git(repo_path, &["config", "url.git@github.com:.insteadOf", "gh:"]);
+
+        git(repo_path, &["config", "url.ssh://git@push.example.com/.pushInsteadOf", "gh:"]);
+
+        let remotes = get_remote_urls(repo_path).await.unwrap();
+
+        assert_eq!(remotes.len(), 2);
+        assert_eq!(remotes[0].direction, Direction::Fetch);
+        assert_eq!(remotes[0].raw_url, "gh:org/repo");
+        assert_eq!(remotes[0].url, "git@github.com:org/repo");
+        assert_eq!(remotes[1].direction, Direction::Push);
+        assert_eq!(remotes[1].raw_url, "gh:org/repo");
+        assert_eq!(remotes[1].url, "ssh://git@push.example.com/org/repo");
+    }
+
+    #[tokio::test]
+    async fn test_push_urls() {
+        let temp_dir = create_test_repo_with_remote("https://github.com/upstream/repo.git").await;
+        let repo_path = temp_dir.path();
 
         let remotes = get_remote_urls(repo_path).await.unwrap();
+        assert_eq!(remotes.len(), 1);
+        assert_eq!(remotes[0].direction, Direction::Both);
+
+        // Fetch from upstream, push to a fork
+        git(repo_path, &["remote", "set-url", "--add", "--push", "origin", "git@github.com:me/repo.git"]);
+
+        let remotes = get_remote_urls(repo_path).await.unwrap();
+        assert_eq!(remotes.len(), 2);
+        assert_eq!(remotes[0].direction, Direction::Fetch);
+        assert_eq!(remotes[0].url, "https://github.com/upstream/repo.git");
+        assert_eq!(remotes[1].direction, Direction::Push);
+        assert_eq!(remotes[1].url, "git@github.com:me/repo.git");
+    }
 
+    #[tokio::test]
+    async fn test_multiple_url_entries() {
+        let temp_dir = create_test_repo_with_remote("https://github.com/org/repo.git").await;
+        let repo_path = temp_dir.path();
+
+        git(repo_path, &["remote", "set-url", "--add", "origin", "https://gitlab.com/org/repo.git"]);
+
+        // The first url is fetched from and pushed to, the second only pushed to
+        let remotes = get_remote_urls(repo_path).await.unwrap();
         assert_eq!(remotes.len(), 2);
-        assert!(remotes.iter().any(|(name, _)| name == "origin"));
-        assert!(remotes.iter().any(|(name, _)| name == "upstream"));
+        assert_eq!(remotes[0].direction, Direction::Both);
+        assert_eq!(remotes[0].url, "https://github.com/org/repo.git");
+        assert_eq!(remotes[1].direction, Direction::Push);
+        assert_eq!(remotes[1].url, "https://gitlab.com/org/repo.git");
+    }
+
+    #[test]
+    fn test_activity_window() {
+        const DAY: i64 = 24 * 60 * 60;
+        let now = 1_700_000_000;
+        let days = |count: u64| Some(Duration::from_secs(count * DAY as u64));
+
+        let everything = ActivityWindow::default();
+        assert!(everything.is_empty());
+        assert!(everything.contains(None, now));
+        assert!(everything.contains(Some(0), now));
+
+        let active = ActivityWindow {
+            active_since: days(14),
+            ..ActivityWindow::default()
+        };
+        assert!(active.contains(Some(now), now));
+        assert!(active.contains(Some(now - 14 * DAY), now));
+        assert!(!active.contains(Some(now - 14 * DAY - 1), now));
+        assert!(!active.contains(None, now));
+
+        let stale = ActivityWindow {
+            stale_for: days(180),
+            ..ActivityWindow::default()
+        };
+        assert!(stale.contains(Some(now - 180 * DAY - 1), now));
+        assert!(!stale.contains(Some(now - 180 * DAY), now));
+        assert!(stale.contains(None, now));
+
+        // Active within a year but stale for a month
+        let between = ActivityWindow {
+            active_since: days(365),
+            stale_for: days(30),
+        };
+        assert!(between.contains(Some(now - 60 * DAY), now));
+        assert!(!between.contains(Some(now - 10 * DAY), now));
+        assert!(!between.contains(Some(now - 400 * DAY), now));
+        assert!(!between.contains(None, now));
+
+        // Ages beyond the epoch cover all of history
+        let forever = ActivityWindow {
+            stale_for: Some(Duration::from_secs(u64::MAX)),
+            ..ActivityWindow::default()
+        };
+        assert!(!forever.contains(Some(0), now));
+        assert!(forever.contains(None, now));
+    }
+
+    #[test]
+    fn test_match_on() {
+        assert!(MatchOn::Fetch.accepts(Direction::Both));
+        assert!(!MatchOn::Fetch.accepts(Direction::Push));
+        assert!(MatchOn::Push.accepts(Direction::Both));
+        assert!(!MatchOn::Push.accepts(Direction::Fetch));
+        assert!(MatchOn::Any.accepts(Direction::Fetch));
+    }
+
+    #[tokio::test]
+    async fn test_follows_local_remotes() {
+        let upstream = create_test_repo_with_remote("git@github.com:org/foo.git").await;
+        let middle = create_test_repo_with_remote(upstream.path().to_str().unwrap()).await;
+        let experiment = create_test_repo_with_remote(&format!("file://{}", middle.path().display())).await;
+
+        let remotes = get_remote_urls(experiment.path()).await.unwrap();
+        let followed: Vec<_> = remotes.iter().filter(|r| !r.via.is_empty()).collect();
+
+        // The experiment's origin leads through middle to upstream's GitHub remote
+        let github = followed.iter().find(|r| r.url == "git@github.com:org/foo.git").unwrap();
+        assert_eq!(github.name, "origin");
+        assert_eq!(
+            github.via,
+            vec![middle.path().canonicalize().unwrap(), upstream.path().canonicalize().unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_remote_cycle() {
+        let first = create_test_repo_with_remote("https://github.com/org/first.git").await;
+        let second = create_test_repo_with_remote(first.path().to_str().unwrap()).await;
+
+        // Point first back at second via a relative path
+        let relative = format!("../{}", second.path().file_name().unwrap().to_str().unwrap());
+        git(first.path(), &["remote", "add", "mirror", &relative]);
+
+        let remotes = get_remote_urls(second.path()).await.unwrap();
+        assert!(remotes.iter().any(|r| r.url == "https://github.com/org/first.git" && r.via.len() == 1));
+        // second is never revisited through first's "mirror" remote
+        assert!(remotes.iter().all(|r| r.via.len() <= 1));
+    }
+
+    #[tokio::test]
+    async fn test_submodule_paths() {
+        let temp_dir = create_test_repo_with_remote("https://github.com/org/super.git").await;
+        std::fs::write(
+            temp_dir.path().join(".gitmodules"),
+            "[submodule \"vendor/foo\"]\n\tpath = vendor/foo\n\turl = https://github.com/org/foo.git\n",
+        )
+        .unwrap();
+
+        assert_eq!(submodule_paths(temp_dir.path()).await, vec![temp_dir.path().join("vendor/foo")]);
+
+        let plain = create_test_repo_with_remote("https://github.com/org/plain.git").await;
+        assert!(submodule_paths(plain.path()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_submodule_urls() {
+        let temp_dir = create_test_repo_with_remote("git@github.com:org/app.git").await;
+        let repo_path = temp_dir.path();
+        std::fs::write(
+            repo_path.join(".gitmodules"),
+            "[submodule \"lib\"]\n\tpath = vendor/lib\n\turl = ../lib.git\n\
+             [submodule \"tools\"]\n\tpath = tools\n\turl = https://github.com/org/tools.git\n",
+        )
+        .unwrap();
+
+        // `git submodule init` copies the URL into .git/config, where it may be changed
+        git(repo_path, &["config", "submodule.tools.url", "https://github.com/me/tools.git"]);
+
+        let submodules = get_submodule_urls(&open(repo_path)).unwrap();
+        let urls: Vec<(&str, &str)> = submodules.iter().map(|s| (s.name.as_str(), s.url.as_str())).collect();
+        assert_eq!(
+            urls,
+            vec![
+                ("lib", "git@github.com:org/lib.git"),
+                ("tools", "https://github.com/org/tools.git"),
+                ("tools", "https://github.com/me/tools.git"),
+            ]
+        );
+        assert_eq!(submodules[0].path, PathBuf::from("vendor/lib"));
+        // Nothing committed, so nothing pinned
+        assert_eq!(submodules[0].pinned, None);
+    }
+
+    #[test]
+    fn test_resolve_relative_url() {
+        assert_eq!(resolve_relative_url("git@github.com:org/app.git", "../lib.git"), "git@github.com:org/lib.git");
+        assert_eq!(resolve_relative_url("git@github.com:org/app", "../../other/lib"), "git@github.com:other/lib");
+        assert_eq!(
+            resolve_relative_url("https://github.com/org/app/", "../lib"),
+            "https://github.com/org/lib"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_status() {
+        let temp_dir = create_test_repo_with_remote("https://github.com/org/repo.git").await;
+        let repo_path = temp_dir.path();
+
+        git(repo_path, &["checkout", "-b", "main"]);
+        let status = get_status(&mut open(repo_path)).unwrap();
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.head, None);
+
+        std::fs::write(repo_path.join("tracked.txt"), "one").unwrap();
+        git(repo_path, &["add", "tracked.txt"]);
+        git(repo_path, &["commit", "-m", "init"]);
+        std::fs::write(repo_path.join("tracked.txt"), "two").unwrap();
+        std::fs::write(repo_path.join("new.txt"), "new").unwrap();
+
+        let status = get_status(&mut open(repo_path)).unwrap();
+        assert_eq!((status.dirty, status.untracked), (1, 1));
+
+        git(repo_path, &["stash"]);
+        git(repo_path, &["checkout", "--detach"]);
+        let status = get_status(&mut open(repo_path)).unwrap();
+        assert_eq!(status.stashes, 1);
+        assert_eq!(status.branch, None);
+        assert!(status.head.is_some());
+        assert_eq!(status.operation, None);
+    }
+
+    #[tokio::test]
+    async fn test_activity() {
+        let temp_dir = create_test_repo_with_remote("https://github.com/org/repo.git").await;
+        let repo_path = temp_dir.path();
+        let git_at = |args: &[&str], date: &str| {
+            git_command(repo_path, args)
+                .env("GIT_AUTHOR_DATE", date)
+                .env("GIT_COMMITTER_DATE", date)
+                .output()
+                .unwrap();
+        };
+
+        assert_eq!(get_activity(&open(repo_path)).unwrap(), Activity::default());
+
+        git_at(&["commit", "--allow-empty", "-m", "init"], "@1600000000 +0000");
+        git_at(&["checkout", "-b", "feature"], "@1700000000 +0000");
+
+        let activity = get_activity(&open(repo_path)).unwrap();
+        assert_eq!(activity.head_commit, Some(1600000000));
+        assert_eq!(activity.last_checkout, Some(1700000000));
+        assert_eq!(activity.last_fetch, None);
+        assert_eq!(activity.latest(), Some(1700000000));
+
+        std::fs::write(repo_path.join(".git/FETCH_HEAD"), "").unwrap();
+        let activity = get_activity(&open(repo_path)).unwrap();
+        assert!(activity.last_fetch.unwrap() > 1700000000);
+        assert_eq!(activity.latest(), activity.last_fetch);
+    }
+
+    #[tokio::test]
+    async fn test_commit_lookup() {
+        let temp_dir = create_test_repo_with_remote("https://github.com/org/repo.git").await;
+        let repo_path = temp_dir.path();
+
+        assert!(root_commits(repo_path).await.unwrap().is_empty());
+
+        git(repo_path, &["commit", "--allow-empty", "-m", "root"]);
+        let root = git(repo_path, &["rev-parse", "HEAD"]);
+        git(repo_path, &["commit", "--allow-empty", "-m", "second"]);
+        // A second history joined in through an unrelated branch
+        git(repo_path, &["checkout", "--orphan", "other"]);
+        git(repo_path, &["commit", "--allow-empty", "-m", "other root"]);
+        let other_root = git(repo_path, &["rev-parse", "HEAD"]);
+
+        let mut roots = root_commits(repo_path).await.unwrap();
+        roots.sort();
+        let mut expected = vec![root.clone(), other_root];
+        expected.sort();
+        assert_eq!(roots, expected);
+
+        assert_eq!(find_commit(&open(repo_path), &root).unwrap(), Some(root.clone()));
+        assert_eq!(find_commit(&open(repo_path), &root[..10]).unwrap(), Some(root.clone()));
+        assert_eq!(
+            find_commit(&open(repo_path), "0123456789abcdef0123456789abcdef01234567").unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_match_refs() {
+        let temp_dir = create_test_repo_with_remote("https://github.com/org/repo.git").await;
+        let repo_path = temp_dir.path();
+        let filter = |has_branch: Option<&str>, has_tag: Option<&str>, on_branch: Option<&str>| RefFilter {
+            has_branch: has_branch.map(|name| Pattern::new(name).unwrap()),
+            has_tag: has_tag.map(|name| Pattern::new(name).unwrap()),
+            on_branch: on_branch.map(|name| Pattern::new(name).unwrap()),
+        };
+
+        git(repo_path, &["checkout", "-b", "main"]);
+        git(repo_path, &["commit", "--allow-empty", "-m", "init"]);
+        git(repo_path, &["branch", "feature/x"]);
+        git(repo_path, &["branch", "feature/y"]);
+        git(repo_path, &["tag", "v2.1.0"]);
+
+        let refs = match_refs(&open(repo_path), &filter(Some("feature/*"), Some("v2.1.0"), Some("main")))
+            .unwrap()
+            .unwrap();
+        assert_eq!(refs.branches, vec!["feature/x", "feature/y"]);
+        assert_eq!(refs.tags, vec!["v2.1.0"]);
+        assert_eq!(refs.head.as_deref(), Some("main"));
+
+        assert_eq!(match_refs(&open(repo_path), &filter(Some("feature/z"), None, None)).unwrap(), None);
+        assert_eq!(match_refs(&open(repo_path), &filter(None, Some("v3.*"), None)).unwrap(), None);
+        assert_eq!(match_refs(&open(repo_path), &filter(None, None, Some("feature/x"))).unwrap(), None);
+
+        git(repo_path, &["checkout", "--detach"]);
+        assert_eq!(match_refs(&open(repo_path), &filter(None, None, Some("main"))).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_mirror_remote() {
+        let source = create_test_repo_with_remote("https://github.com/org/repo.git").await;
+        let mirror = TempDir::new().unwrap();
+        let mirror_path = mirror.path().join("repo.git");
+
+        git(mirror.path(), &["clone", "--mirror", source.path().to_str().unwrap(), "repo.git"]);
+
+        let remotes = get_remote_urls(&mirror_path).await.unwrap();
+        let origin = remotes.iter().find(|r| r.via.is_empty()).unwrap();
+        assert!(origin.mirror);
+        // The source's own remote is reached through the local mirror origin
+        assert!(remotes.iter().any(|r| r.url == "https://github.com/org/repo.git"));
+    }
+
+    #[tokio::test]
+    async fn test_linked_worktrees() {
+        let main = create_test_repo_with_remote("https://github.com/org/repo.git").await;
+        let outside = TempDir::new().unwrap();
+        let wt_path = outside.path().join("feature");
+
+        git(main.path(), &["commit", "--allow-empty", "-m", "init"]);
+        git(main.path(), &["worktree", "add", "-b", "feature", wt_path.to_str().unwrap()]);
+
+        let link = linked_worktree(&wt_path).await.unwrap();
+        assert_eq!(link.main.canonicalize().unwrap(), main.path().canonicalize().unwrap());
+        assert!(link.main_exists);
+        assert_eq!(linked_worktree(main.path()).await, None);
+
+        let worktrees = list_worktrees(&open(main.path())).unwrap();
+        assert_eq!(worktrees.len(), 1);
+        assert_eq!(worktrees[0].branch.as_deref(), Some("feature"));
+        assert!(!worktrees[0].missing);
+
+        // The main repository disappears
+        drop(main);
+        assert!(!linked_worktree(&wt_path).await.unwrap().main_exists);
+    }
+
+    #[test]
+    fn test_local_path_detection() {
+        let base = Path::new("/work/clone");
+
+        assert_eq!(local_path("/srv/git/foo.git", base), Some(PathBuf::from("/srv/git/foo.git")));
+        assert_eq!(local_path("file:///srv/git/foo.git", base), Some(PathBuf::from("/srv/git/foo.git")));
+        assert_eq!(local_path("../foo.git", base), Some(PathBuf::from("/work/clone/../foo.git")));
+        assert_eq!(local_path("git@github.com:org/foo.git", base), None);
+        assert_eq!(local_path("https://github.com/org/foo.git", base), None);
+        assert_eq!(local_path("gh:org/foo", base), None);
+    }
+
+    #[test]
+    fn test_rewrite_rules() {
+        let rewrites = UrlRewrites {
+            instead_of: vec![
+                ("gh:".to_string(), "git@github.com:".to_string()),
+                ("work:".to_string(), "git@gitlab.corp.com:team/".to_string()),
+                ("work:special/".to_string(), "https://special.corp.com/".to_string()),
+            ],
+            push_instead_of: vec![("https://github.com/".to_string(), "git@github.com:".to_string())],
+        };
+
+        assert_eq!(rewrites.fetch_url("gh:org/repo"), "git@github.com:org/repo");
+        assert_eq!(rewrites.fetch_url("work:repo"), "git@gitlab.corp.com:team/repo");
+        // Longest prefix wins
+        assert_eq!(rewrites.fetch_url("work:special/repo"), "https://special.corp.com/repo");
+        assert_eq!(rewrites.fetch_url("https://example.com/a/b"), "https://example.com/a/b");
+
+        // pushInsteadOf only affects pushes
+        assert_eq!(rewrites.fetch_url("https://github.com/org/repo"), "https://github.com/org/repo");
+        assert_eq!(rewrites.push_url("https://github.com/org/repo"), "git@github.com:org/repo");
+        assert_eq!(rewrites.push_url("gh:org/repo"), "git@github.com:org/repo");
     }
 }
@@ -0,0 +1,180 @@
+use crate::error::{FsgitError, Result};
+use crate::git::RemoteUrl;
+use crate::matcher::RepoId;
+use glob::{MatchOptions, Pattern};
+use std::fmt;
+
+/// Hosts, owners and email addresses compare case-insensitively
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+/// Effective `user.name` and `user.email` commits in a repository are made with
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Identity {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.name, &self.email) {
+            (Some(name), Some(email)) => write!(f, "{} <{}>", name, email),
+            (Some(name), None) => write!(f, "{} (no email)", name),
+            (None, Some(email)) => write!(f, "<{}> (no name)", email),
+            (None, None) => write!(f, "not set"),
+        }
+    }
+}
+
+/// Identity required of repositories with a remote on a given host (and owner),
+/// e.g. remotes on gitlab.corp.com must commit as *@corp.com
+#[derive(Debug, Clone)]
+pub struct IdentityRule {
+    host: Pattern,
+    owner: Option<Pattern>,
+    email: Option<Pattern>,
+    name: Option<Pattern>,
+}
+
+impl IdentityRule {
+    /// Compile a rule; every field may use glob wildcards
+    pub fn new(host: &str, owner: Option<&str>, email: Option<&str>, name: Option<&str>) -> Result<Self> {
+        let compile = |glob: &str| {
+            Pattern::new(glob).map_err(|e| FsgitError::Config(format!("identity rule '{}': {}", glob, e)))
+        };
+
+        Ok(Self {
+            host: compile(host)?,
+            owner: owner.map(compile).transpose()?,
+            email: email.map(compile).transpose()?,
+            name: name.map(compile).transpose()?,
+        })
+    }
+
+    /// Whether the rule governs repositories with a remote pointing at `id`
+    pub fn applies_to(&self, id: &RepoId) -> bool {
+        id.host
+            .as_deref()
+            .is_some_and(|host| self.host.matches_with(host, GLOB_OPTIONS))
+            && self
+                .owner
+                .as_ref()
+                .is_none_or(|owner| owner.matches_with(&id.namespace, GLOB_OPTIONS))
+    }
+
+    /// Describe how `identity` breaks the rule, or `None` when it complies
+    pub fn check(&self, identity: &Identity) -> Option<String> {
+        let mismatch = |field: &str, value: &Option<String>, required: &Pattern| match value {
+            Some(value) if required.matches_with(value, GLOB_OPTIONS) => None,
+            Some(value) => Some(format!("{} {} does not match {}", field, value, required)),
+            None => Some(format!("{} is not set, expected {}", field, required)),
+        };
+
+        let problems: Vec<String> = [
+            self.email.as_ref().and_then(|email| mismatch("user.email", &identity.email, email)),
+            self.name.as_ref().and_then(|name| mismatch("user.name", &identity.name, name)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        (!problems.is_empty()).then(|| problems.join("; "))
+    }
+}
+
+impl fmt::Display for IdentityRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.host)?;
+        if let Some(owner) = &self.owner {
+            write!(f, "/{}", owner)?;
+        }
+        Ok(())
+    }
+}
+
+/// A rule a repository's identity breaks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentityViolation {
+    /// Remote the rule applied through
+    pub remote: String,
+    /// Host (and owner) the rule covers, e.g. "gitlab.corp.com" or "github.com/corp-*"
+    pub rule: String,
+    pub problem: String,
+}
+
+/// Check a repository's identity against every rule that applies to one of its remotes
+/// Each broken rule is reported once, through the first remote it applies to
+pub fn audit(identity: &Identity, remotes: &[RemoteUrl], rules: &[IdentityRule]) -> Vec<IdentityViolation> {
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let remote = remotes
+                .iter()
+                .find(|remote| RepoId::parse(&remote.url).is_some_and(|id| rule.applies_to(&id)))?;
+            Some(IdentityViolation {
+                remote: remote.name.clone(),
+                rule: rule.to_string(),
+                problem: rule.check(identity)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::tests::remote;
+
+    fn identity(name: &str, email: &str) -> Identity {
+        Identity {
+            name: Some(name.to_string()),
+            email: Some(email.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_rule_scope() {
+        let rule = IdentityRule::new("gitlab.corp.com", None, Some("*@corp.com"), None).unwrap();
+        assert!(rule.applies_to(&RepoId::parse("git@GitLab.corp.com:team/app.git").unwrap()));
+        assert!(!rule.applies_to(&RepoId::parse("git@github.com:team/app.git").unwrap()));
+
+        let rule = IdentityRule::new("github.com", Some("corp-*"), Some("*@corp.com"), None).unwrap();
+        assert!(rule.applies_to(&RepoId::parse("https://github.com/corp-infra/tools").unwrap()));
+        assert!(!rule.applies_to(&RepoId::parse("https://github.com/me/dotfiles").unwrap()));
+        assert_eq!(rule.to_string(), "github.com/corp-*");
+    }
+
+    #[test]
+    fn test_audit() {
+        let rules = vec![
+            IdentityRule::new("gitlab.corp.com", None, Some("*@corp.com"), None).unwrap(),
+            IdentityRule::new("github.com", None, Some("*@personal.dev"), Some("Jane Doe")).unwrap(),
+        ];
+        let remotes = vec![
+            remote("origin", "git@gitlab.corp.com:team/app.git"),
+            remote("mirror", "git@gitlab.corp.com:backup/app.git"),
+        ];
+
+        assert!(audit(&identity("Jane Doe", "jane@Corp.com"), &remotes, &rules).is_empty());
+
+        let violations = audit(&identity("Jane Doe", "jane@personal.dev"), &remotes, &rules);
+        assert_eq!(
+            violations,
+            vec![IdentityViolation {
+                remote: "origin".to_string(),
+                rule: "gitlab.corp.com".to_string(),
+                problem: "user.email jane@personal.dev does not match *@corp.com".to_string(),
+            }]
+        );
+
+        let remotes = vec![remote("origin", "https://github.com/jane/dotfiles.git")];
+        let violations = audit(&Identity::default(), &remotes, &rules);
+        assert_eq!(
+            violations[0].problem,
+            "user.email is not set, expected *@personal.dev; user.name is not set, expected Jane Doe"
+        );
+    }
+}
@@ -1,14 +1,20 @@
 pub mod cli;
+pub mod config;
 pub mod error;
 pub mod git;
+pub mod identity;
 pub mod matcher;
 pub mod output;
 pub mod progress;
 pub mod scanner;
+pub mod ssh_config;
 
 // Re-export commonly used types for convenience
-pub use cli::Cli;
+pub use cli::{Cli, SortOrder};
+pub use config::Config;
 pub use error::{FsgitError, Result};
-pub use matcher::RepositoryPattern;
+pub use identity::{Identity, IdentityRule, IdentityViolation};
+pub use matcher::{GerritHosts, Protocol, RepoAliases, RepoId, RepositoryPattern};
 pub use progress::{ProgressMessage, ProgressTracker};
-pub use scanner::{MatchResult, Scanner};
+pub use scanner::{MatchResult, MatchedRemote, MatchedSubmodule, Orphan, Role, Scanner};
+pub use ssh_config::SshConfig;
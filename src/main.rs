@@ -1,28 +1,24 @@
-mod cli;
-mod error;
-mod git;
-mod matcher;
-mod output;
-mod progress;
-mod scanner;
-
 use clap::Parser;
-use cli::Cli;
-use colored::Colorize;
-use matcher::RepositoryPattern;
-use progress::{ProgressMessage, ProgressTracker};
-use scanner::Scanner;
+use fsgitwatch::git::{self, RefFilter};
+use fsgitwatch::output;
+use fsgitwatch::{Cli, Config, ProgressMessage, ProgressTracker, RepositoryPattern, Scanner, SortOrder, SshConfig};
+use std::cmp::Reverse;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 
+/// How many "did you mean" suggestions to show when nothing matches
+const MAX_SUGGESTIONS: usize = 5;
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Parse CLI arguments
     let cli = Cli::parse();
 
+    // Collect patterns from arguments and --patterns-from
+    let (pattern_strs, search_path) = cli.resolve_patterns()?;
+
     // Determine search path (default to current directory)
-    let search_path = cli
-        .search_path
-        .unwrap_or_else(|| std::env::current_dir().expect("Failed to get current directory"));
+    let search_path = search_path.unwrap_or_else(|| std::env::current_dir().expect("Failed to get current directory"));
 
     // Validate that search path exists
     if !search_path.exists() {
@@ -30,12 +26,79 @@ async fn main() -> anyhow::Result<()> {
         std::process::exit(1);
     }
 
-    // Parse repository pattern
-    let pattern = RepositoryPattern::new(&cli.pattern)?;
+    // Load user config (rename/transfer aliases)
+    let config = Config::load(cli.config.as_deref())?;
+    let aliases = Arc::new(config.repo_aliases());
+    let identity_rules = config.identity_rules()?;
+    let gerrit_hosts = config.gerrit_hosts();
+
+    // SSH Host aliases apply to remotes and to SSH URLs given as patterns
+    let ssh_config = SshConfig::load();
+
+    // Clone URLs given as patterns go through the same rewrites as remotes
+    let parse_glob = |pattern: &String| {
+        let resolved = ssh_config.resolve_url(pattern);
+        let resolved = gerrit_hosts.resolve_url(resolved.as_deref().unwrap_or(pattern)).or(resolved);
+        RepositoryPattern::new(resolved.as_deref().unwrap_or(pattern))
+    };
+
+    // Parse repository patterns
+    let parse_pattern = |pattern: &String| {
+        let pattern = if cli.regex {
+            RepositoryPattern::regex(pattern)?
+        } else {
+            parse_glob(pattern)?
+        };
+        Ok(pattern.with_aliases(aliases.clone()))
+    };
+    let patterns = pattern_strs
+        .iter()
+        .map(parse_pattern)
+        .collect::<fsgitwatch::Result<Vec<_>>>()?;
+
+    // Commit lookups, --orphans, --audit-identity and ref filters alone replace patterns;
+    // describe them in place of one
+    let (commits, pattern_strs) = if let Some(sha) = &cli.contains {
+        (vec![sha.clone()], vec![format!("commit {}", sha)])
+    } else if let Some(reference) = &cli.same_root {
+        let roots = git::root_commits(reference).await?;
+        if roots.is_empty() {
+            eprintln!("Error: Reference repository has no commits: {}", reference.display());
+            std::process::exit(1);
+        }
+        (roots, vec![format!("root commit of {}", reference.display())])
+    } else if cli.orphans {
+        (Vec::new(), vec!["repositories without remotes".to_string()])
+    } else if cli.audit_identity {
+        (Vec::new(), vec!["every repository".to_string()])
+    } else if pattern_strs.is_empty() {
+        // Ref filters alone: every repository is checked against them
+        let conditions: Vec<String> = [
+            cli.has_branch.as_ref().map(|name| format!("branch {}", name)),
+            cli.has_tag.as_ref().map(|name| format!("tag {}", name)),
+            cli.on_branch.as_ref().map(|name| format!("HEAD on {}", name)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        (Vec::new(), vec![conditions.join(", ")])
+    } else {
+        (Vec::new(), pattern_strs)
+    };
+    // Exclusions are always globs and name exactly what they say: --regex and
+    // rename aliases only apply to the patterns searched for
+    let excludes = cli
+        .exclude
+        .iter()
+        .map(parse_glob)
+        .collect::<fsgitwatch::Result<Vec<_>>>()?;
 
     // Determine if we should show progress bar
     let show_progress = !cli.json && !cli.no_progress;
 
+    // Sorted listings can only be printed once the scan is complete
+    let stream_matches = show_progress && cli.sort == SortOrder::Found;
+
     // Create progress channel if we're showing progress or in verbose mode
     let (progress_tx, progress_rx) = if show_progress || cli.verbose > 0 {
         let (tx, rx) = mpsc::unbounded_channel();
@@ -44,21 +107,55 @@ async fn main() -> anyhow::Result<()> {
         (None, None)
     };
 
+    // Branch and tag conditions checked alongside the remotes
+    let ref_filter = RefFilter {
+        has_branch: cli.has_branch.clone(),
+        has_tag: cli.has_tag.clone(),
+        on_branch: cli.on_branch.clone(),
+    };
+
+    // Filters can drop repositories whose remotes did match, so near misses mean nothing then
+    let filtered = !ref_filter.is_empty() || cli.active_since.is_some() || cli.stale_for.is_some();
+
     // Create scanner
-    let scanner = Scanner::new(search_path, pattern, cli.max_concurrent, cli.verbose);
+    let scanner = Scanner::new(
+        search_path,
+        patterns.clone(),
+        cli.max_concurrent,
+        cli.verbose,
+        ssh_config,
+    )
+    .with_gerrit_hosts(gerrit_hosts.clone())
+    .with_excludes(excludes)
+    .with_match_on(cli.match_on)
+    .with_forks(cli.forks)
+    .with_all_worktrees(cli.worktrees)
+    .with_nested(cli.nested)
+    .with_submodules(cli.submodules)
+    .with_superprojects(cli.superprojects)
+    .with_status(cli.status)
+    .with_activity(cli.status || cli.orphans || cli.sort == SortOrder::Recent)
+    .with_active_since(cli.active_since)
+    .with_stale_for(cli.stale_for)
+    .with_commits(commits)
+    .with_orphans(cli.orphans)
+    .with_ref_filter(ref_filter.clone())
+    .with_identity_audit(cli.audit_identity)
+    .with_identity_rules(identity_rules);
 
-    // Clone pattern string for tracker
-    let pattern_str = cli.pattern.clone();
+    // Describe what we're searching for in the tracker
+    let pattern_str = match pattern_strs.as_slice() {
+        [single] => single.clone(),
+        many => format!("{} patterns", many.len()),
+    };
 
     // Spawn progress tracker if we have a receiver
-    let tracker_handle = if let Some(rx) = progress_rx {
-        Some(tokio::spawn(async move {
-            let tracker = ProgressTracker::new(rx, show_progress, cli.verbose, pattern_str);
+    let tracker_handle = progress_rx.map(|rx| {
+        tokio::spawn(async move {
+            let tracker = ProgressTracker::new(rx, show_progress, stream_matches, cli.verbose, pattern_str);
             tracker.run().await
-        }))
-    } else {
-        None
-    };
+        })
+    });
 
     // Run async scan
     let scan_results = scanner.scan(progress_tx.clone()).await?;
@@ -69,39 +166,53 @@ async fn main() -> anyhow::Result<()> {
     }
 
     // Get results from progress tracker or use scan results
-    let results = if let Some(handle) = tracker_handle {
+    let mut results = if let Some(handle) = tracker_handle {
         handle.await?
     } else {
         scan_results
     };
 
+    if cli.sort == SortOrder::Recent {
+        // Repositories with no recorded activity go last
+        results.sort_by_key(|result| Reverse(result.activity.and_then(|activity| activity.latest())));
+    }
+
+    // Suggest near misses from the remotes we saw for patterns that matched nothing
+    let seen_repos = scanner.seen_repos().await;
+    let suggestions: Vec<Vec<String>> = (0..pattern_strs.len())
+        .map(|idx| match patterns.get(idx) {
+            Some(pattern) if !filtered && output::results_for_pattern(&results, idx).is_empty() => {
+                pattern.suggestions(&seen_repos, MAX_SUGGESTIONS)
+            }
+            _ => Vec::new(),
+        })
+        .collect();
+
+    let orphaned_worktrees = scanner.orphaned_worktrees().await;
+
     // Output results (only if not in streaming mode)
-    if cli.json {
-        output::print_json(&results, &cli.pattern)?;
-    } else if !show_progress {
-        // If we didn't show progress, print results now
-        output::print_results(&results, &cli.pattern);
-    } else {
-        // Progress bar already printed results, just show summary
-        if results.is_empty() {
-            println!(
-                "\n{}",
-                format!("No repositories found matching '{}'", cli.pattern)
-                    .yellow()
-                    .bold()
-            );
+    if let [pattern] = pattern_strs.as_slice() {
+        if cli.json {
+            output::print_json(&results, pattern, &suggestions[0], &orphaned_worktrees)?;
+        } else if !stream_matches {
+            // If matches weren't streamed, print results now
+            output::print_results(&results, pattern);
+            output::print_suggestions(&suggestions[0]);
         } else {
-            println!(
-                "\n{} {} matching '{}'",
-                "Found".green().bold(),
-                if results.len() == 1 {
-                    format!("{} repository", results.len())
-                } else {
-                    format!("{} repositories", results.len())
-                },
-                cli.pattern.cyan()
-            );
+            // Progress bar already printed results, just show summary
+            output::print_summary(&results, pattern, &suggestions[0]);
         }
+    } else if cli.json {
+        output::print_grouped_json(&results, &pattern_strs, &suggestions, &orphaned_worktrees)?;
+    } else if !stream_matches {
+        output::print_grouped_results(&results, &pattern_strs, &suggestions);
+    } else {
+        output::print_grouped_summary(&results, &pattern_strs, &suggestions);
+    }
+
+    if !cli.json {
+        output::print_orphaned_worktrees(&orphaned_worktrees);
+        output::print_identity_violations(&results);
     }
 
     // Exit with code 0 if found, 1 if not found
@@ -1,101 +1,623 @@
 use crate::error::{FsgitError, Result};
-use git_url_parse::GitUrl;
+use git_url_parse::{GitUrl, Scheme};
+use glob::{MatchOptions, Pattern};
+use regex::{Regex, RegexBuilder};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Glob matching is case-insensitive and `*` never crosses a `/`
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Transport named by a remote URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    /// `ssh://`, `git+ssh://` or scp-like `user@host:path`
+    Ssh,
+    Git,
+    Http,
+    Https,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Protocol::Ssh => write!(f, "ssh"),
+            Protocol::Git => write!(f, "git"),
+            Protocol::Http => write!(f, "http"),
+            Protocol::Https => write!(f, "https"),
+        }
+    }
+}
+
+/// Repository identity parsed from a remote URL
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RepoId {
+    /// Transport the URL uses, when it names one
+    pub protocol: Option<Protocol>,
+    /// User the URL connects as (e.g. "git" in "git@github.com:owner/repo")
+    pub user: Option<String>,
+    /// Hosting server, when the URL names one (e.g. "github.com")
+    pub host: Option<String>,
+    /// Explicit port, when the URL names one
+    pub port: Option<u16>,
+    /// Owner, or the full group path on forges with nested namespaces
+    /// (e.g. "platform/infra/terraform")
+    pub namespace: String,
+    pub name: String,
+}
+
+impl RepoId {
+    /// Parse a remote URL (scp-like, ssh://, https://, git://)
+    /// Returns `None` for local paths and URLs without a namespace/repo path
+    pub fn parse(url: &str) -> Option<Self> {
+        // Try using git-url-parse first
+        match GitUrl::parse(url) {
+            Ok(parsed) => {
+                let protocol = match parsed.scheme {
+                    Scheme::Ssh | Scheme::GitSsh => Some(Protocol::Ssh),
+                    Scheme::Git => Some(Protocol::Git),
+                    Scheme::Http => Some(Protocol::Http),
+                    Scheme::Https => Some(Protocol::Https),
+                    // Local paths carry no namespace information
+                    Scheme::File => return None,
+                    Scheme::Ftp | Scheme::Ftps | Scheme::Unspecified => None,
+                };
+
+                // GitUrl only keeps the segment before the name as owner,
+                // so take the full path to preserve nested namespaces
+                build_repo_id(protocol, parsed.user, parsed.host, parsed.port, &parsed.path)
+            }
+            Err(_) => {
+                // Fallback to manual parsing if git-url-parse fails
+                manual_parse(url)
+            }
+        }
+    }
+
+    /// The "namespace/repo" part of the identity
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.namespace, self.name)
+    }
+
+    /// Lowercased "host/namespace/repo", the same for every clone URL of a repository
+    pub fn canonical(&self) -> String {
+        self.to_string().to_lowercase()
+    }
+}
+
+impl fmt::Display for RepoId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.host {
+            Some(host) => write!(f, "{}/{}/{}", host, self.namespace, self.name),
+            None => write!(f, "{}/{}", self.namespace, self.name),
+        }
+    }
+}
+
+impl Serialize for RepoId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("RepoId", 7)?;
+        state.serialize_field("protocol", &self.protocol)?;
+        state.serialize_field("user", &self.user)?;
+        state.serialize_field("host", &self.host)?;
+        state.serialize_field("port", &self.port)?;
+        state.serialize_field("namespace", &self.namespace)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("canonical", &self.canonical())?;
+        state.end()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum PatternKind {
+    /// Literal namespace/repo, compared case-insensitively
+    Exact,
+    /// Shell-style wildcards (`*`, `?`, `[...]`) applied to namespace and repo;
+    /// `*` matches within a single namespace segment
+    Glob { namespace: Pattern, repo: Pattern },
+    /// Regular expression matched against `namespace/repo` and `host/namespace/repo`
+    Regex(Regex),
+}
 
 #[derive(Debug, Clone)]
 pub struct RepositoryPattern {
-    owner: String,
+    /// Only match remotes on this host; `None` matches any host
+    host: Option<String>,
+    /// Full namespace path, e.g. "owner" or "group/subgroup"
+    namespace: String,
     repo: String,
+    kind: PatternKind,
+    aliases: Arc<RepoAliases>,
+}
+
+/// Old and new names of renamed or transferred repositories
+/// Names are "namespace/repo", compared case-insensitively on any host
+#[derive(Debug, Default)]
+pub struct RepoAliases {
+    /// (old name, current name) pairs, lowercased
+    renames: Vec<(String, String)>,
+    /// Lowercased current name -> as written in the config, for display
+    spellings: HashMap<String, String>,
+}
+
+impl RepoAliases {
+    /// Build from (old name, current name) pairs
+    pub fn new<I, S>(renames: I) -> Self
+    where
+        I: IntoIterator<Item = (S, S)>,
+        S: AsRef<str>,
+    {
+        let trim = |name: &str| name.trim().trim_matches('/').to_string();
+        let mut aliases = Self::default();
+        for (old, new) in renames {
+            let new = trim(new.as_ref());
+            aliases.renames.push((trim(old.as_ref()).to_lowercase(), new.to_lowercase()));
+            aliases.spellings.insert(new.to_lowercase(), new);
+        }
+        aliases
+    }
+
+    /// Every other name the repository is known by, following rename chains
+    fn other_names(&self, id: &RepoId) -> Vec<RepoId> {
+        if self.renames.is_empty() {
+            return Vec::new();
+        }
+
+        let own = id.path().to_lowercase();
+        let mut names = vec![own.clone()];
+        let mut idx = 0;
+        while idx < names.len() {
+            let name = names[idx].clone();
+            for (old, new) in &self.renames {
+                for (from, to) in [(old, new), (new, old)] {
+                    if *from == name && !names.contains(to) {
+                        names.push(to.clone());
+                    }
+                }
+            }
+            idx += 1;
+        }
+
+        names
+            .into_iter()
+            .skip(1)
+            .filter_map(|name| {
+                let (namespace, repo) = split_namespace_path(&name)?;
+                Some(RepoId {
+                    namespace,
+                    name: repo,
+                    ..id.clone()
+                })
+            })
+            .collect()
+    }
+
+    /// The current name, as written in the config, when `id` uses an old name,
+    /// following rename chains
+    fn current_name(&self, id: &RepoId) -> Option<String> {
+        let mut name = id.path().to_lowercase();
+        let mut renamed = None;
+
+        // Bounded by the table size so a cyclic table cannot loop forever
+        for _ in 0..self.renames.len() {
+            match self.renames.iter().find(|(old, _)| *old == name) {
+                Some((_, new)) => {
+                    name = new.clone();
+                    renamed = Some(new.clone());
+                }
+                None => break,
+            }
+        }
+
+        renamed.map(|name| self.spellings.get(&name).cloned().unwrap_or(name))
+    }
 }
 
 impl RepositoryPattern {
     /// Create a new repository pattern from "owner/repo" format
+    /// Nested namespaces are written out in full, e.g. "group/subgroup/project"
+    /// Any segment may contain glob wildcards, e.g. "anthropics/*" or "*/claude-*"
+    /// The pattern may be host-qualified as "host/owner/repo" or "host:owner/repo",
+    /// or be a full clone URL, which is reduced to its host and path
     pub fn new(pattern: &str) -> Result<Self> {
-        let parts: Vec<&str> = pattern.split('/').collect();
-        if parts.len() != 2 {
-            return Err(FsgitError::InvalidPattern(pattern.to_string()));
+        if is_clone_url(pattern) {
+            return Self::from_url(pattern);
         }
 
-        let owner = parts[0].trim();
-        let repo = parts[1].trim();
-
-        if owner.is_empty() || repo.is_empty() {
+        let (host, path) = split_host(pattern);
+        let path = path.trim();
+        if host.is_some_and(str::is_empty) || path.starts_with('/') || path.ends_with('/') {
             return Err(FsgitError::InvalidPattern(pattern.to_string()));
         }
 
+        let (namespace, repo) =
+            split_namespace_path(path).ok_or_else(|| FsgitError::InvalidPattern(pattern.to_string()))?;
+
+        let kind = if is_glob(&namespace) || is_glob(&repo) {
+            let compile = |segment: &str| {
+                Pattern::new(segment).map_err(|e| FsgitError::InvalidPattern(format!("{}: {}", pattern, e)))
+            };
+            PatternKind::Glob {
+                namespace: compile(&namespace)?,
+                repo: compile(&repo)?,
+            }
+        } else {
+            PatternKind::Exact
+        };
+
         Ok(Self {
-            owner: owner.to_string(),
-            repo: repo.to_string(),
+            host: host.map(|h| h.to_string()),
+            namespace,
+            repo,
+            kind,
+            aliases: Arc::default(),
+        })
+    }
+
+    /// Create an exact pattern from a clone URL (scp-like, ssh://, https://, git://)
+    /// The protocol, user and port are dropped so any clone of the same repo matches
+    pub fn from_url(url: &str) -> Result<Self> {
+        let id = RepoId::parse(url.trim()).ok_or_else(|| FsgitError::InvalidPattern(url.to_string()))?;
+
+        Ok(Self {
+            host: id.host,
+            namespace: id.namespace,
+            repo: id.name,
+            kind: PatternKind::Exact,
+            aliases: Arc::default(),
+        })
+    }
+
+    /// Create a pattern from a regular expression matched against "namespace/repo"
+    /// and, for remotes with a known host, "host/namespace/repo"
+    pub fn regex(pattern: &str) -> Result<Self> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| FsgitError::InvalidRegex(e.to_string()))?;
+
+        Ok(Self {
+            host: None,
+            namespace: String::new(),
+            repo: String::new(),
+            kind: PatternKind::Regex(regex),
+            aliases: Arc::default(),
         })
     }
 
     /// Check if a remote URL matches this pattern
     pub fn matches(&self, remote_url: &str) -> bool {
-        // Try using git-url-parse first
-        match GitUrl::parse(remote_url) {
-            Ok(parsed) => {
-                // Extract owner and repo from parsed URL
-                let url_owner = parsed.owner.as_deref().unwrap_or("");
-                let url_repo = self.normalize_repo_name(&parsed.name);
+        self.match_remote(remote_url).is_some()
+    }
+
+    /// Consult a rename/transfer alias table when matching
+    pub fn with_aliases(mut self, aliases: Arc<RepoAliases>) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Match a remote URL against this pattern
+    /// A remote also matches when it uses another name of an aliased repository
+    /// Returns the concrete repository the URL resolved to when it matches
+    pub fn match_remote(&self, remote_url: &str) -> Option<RepoId> {
+        let id = RepoId::parse(remote_url)?;
+        self.matches_repo(&id).then_some(id)
+    }
 
-                self.owner.eq_ignore_ascii_case(url_owner) && self.repo.eq_ignore_ascii_case(&url_repo)
+    /// Whether `id`, or another name of it in the alias table, matches this pattern
+    fn matches_repo(&self, id: &RepoId) -> bool {
+        self.matches_id(id) || self.aliases.other_names(id).iter().any(|alt| self.matches_id(alt))
+    }
+
+    /// Match a remote that has this pattern's repository name under a different owner,
+    /// as the origin of a fork does
+    /// Only a literal repository name identifies a fork: regex patterns and
+    /// wildcard repository segments never match one
+    pub fn match_fork(&self, remote_url: &str) -> Option<RepoId> {
+        let id = RepoId::parse(remote_url)?;
+
+        if let Some(host) = &self.host {
+            if !id.host.as_deref().is_some_and(|h| h.eq_ignore_ascii_case(host)) {
+                return None;
             }
-            Err(_) => {
-                // Fallback to manual parsing if git-url-parse fails
-                self.manual_parse(remote_url)
+        }
+
+        let matched = match &self.kind {
+            PatternKind::Exact => {
+                self.repo.eq_ignore_ascii_case(&id.name) && !self.namespace.eq_ignore_ascii_case(&id.namespace)
+            }
+            PatternKind::Glob {
+                namespace: namespace_glob,
+                ..
+            } => {
+                !is_glob(&self.repo)
+                    && self.repo.eq_ignore_ascii_case(&id.name)
+                    && !namespace_glob.matches_with(&id.namespace, GLOB_OPTIONS)
+            }
+            PatternKind::Regex(_) => false,
+        };
+
+        matched.then_some(id)
+    }
+
+    /// Current name of a repository when `id` is a stale alias of it
+    pub fn renamed_to(&self, id: &RepoId) -> Option<String> {
+        self.aliases.current_name(id)
+    }
+
+    /// Check a parsed repository identity against this pattern
+    fn matches_id(&self, id: &RepoId) -> bool {
+        if let Some(host) = &self.host {
+            if !id.host.as_deref().is_some_and(|h| h.eq_ignore_ascii_case(host)) {
+                return false;
+            }
+        }
+
+        match &self.kind {
+            PatternKind::Exact => {
+                self.namespace.eq_ignore_ascii_case(&id.namespace) && self.repo.eq_ignore_ascii_case(&id.name)
+            }
+            PatternKind::Glob {
+                namespace: namespace_glob,
+                repo: repo_glob,
+            } => {
+                namespace_glob.matches_with(&id.namespace, GLOB_OPTIONS)
+                    && repo_glob.matches_with(&id.name, GLOB_OPTIONS)
+            }
+            PatternKind::Regex(regex) => {
+                regex.is_match(&id.path()) || (id.host.is_some() && regex.is_match(&id.to_string()))
             }
         }
     }
 
-    /// Normalize repository name by stripping .git suffix
-    fn normalize_repo_name(&self, repo_name: &str) -> String {
-        repo_name.trim_end_matches(".git").to_string()
+    /// Suggest repositories close to this pattern, closest first
+    /// Catches typos ("anthropic/claude-code") and repos that moved to another owner
+    /// Only literal patterns get suggestions; globs and regexes are already fuzzy
+    /// Repositories the pattern matches are never suggested: when they went
+    /// unreported, filters such as --not dropped them
+    pub fn suggestions<'a>(&self, seen: impl IntoIterator<Item = &'a RepoId>, limit: usize) -> Vec<String> {
+        if !matches!(self.kind, PatternKind::Exact) {
+            return Vec::new();
+        }
+
+        let wanted = format!("{}/{}", self.namespace, self.repo).to_lowercase();
+        let max_distance = (wanted.len() / 3).max(2);
+
+        let mut candidates: Vec<(usize, String)> = seen
+            .into_iter()
+            .filter(|id| !self.matches_repo(id))
+            .filter(|id| {
+                self.host.as_deref().is_none_or(|host| {
+                    id.host.as_deref().is_some_and(|h| h.eq_ignore_ascii_case(host))
+                })
+            })
+            .filter_map(|id| {
+                let path = id.path();
+                let distance = strsim::levenshtein(&wanted, &path.to_lowercase());
+                let transferred = id.name.eq_ignore_ascii_case(&self.repo);
+                (distance <= max_distance || transferred).then_some((distance, path))
+            })
+            .collect();
+
+        candidates.sort();
+        candidates.dedup_by(|a, b| a.1.eq_ignore_ascii_case(&b.1));
+        candidates.into_iter().take(limit).map(|(_, path)| path).collect()
     }
+}
+
+/// Manual parsing fallback for edge cases
+fn manual_parse(url: &str) -> Option<RepoId> {
+    // Try to extract host/owner/repo from various URL formats
+    // Pattern: [scheme://][user@]host[:/]owner/repo(.git)?
+
+    let (protocol, url) = if let Some(rest) = url.strip_prefix("https://") {
+        (Some(Protocol::Https), rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        (Some(Protocol::Http), rest)
+    } else if let Some(rest) = url.strip_prefix("ssh://") {
+        (Some(Protocol::Ssh), rest)
+    } else {
+        (None, url)
+    };
+
+    // Split off a user, e.g. "git@"
+    let (user, url) = match url.split_once('@') {
+        Some((user, rest)) if !user.contains(['/', ':']) => (Some(user.to_string()), rest),
+        _ => (None, url),
+    };
+
+    // Look for owner/repo pattern
+    // Examples:
+    // github.com:owner/repo.git
+    // github.com/owner/repo.git
+    // gitlab.com/group/subgroup/repo
+
+    let (host, path, protocol) = if let Some(colon_pos) = url.find(':') {
+        // SSH format: github.com:owner/repo
+        let protocol = protocol.or(user.is_some().then_some(Protocol::Ssh));
+        (&url[..colon_pos], &url[colon_pos + 1..], protocol)
+    } else if let Some(slash_pos) = url.find('/') {
+        // HTTPS format: github.com/owner/repo
+        (&url[..slash_pos], &url[slash_pos + 1..], protocol)
+    } else {
+        return None;
+    };
+
+    build_repo_id(protocol, user, (!host.is_empty()).then(|| host.to_string()), None, path)
+}
+
+/// Hosts serving Gerrit, listed in the config file; over HTTP(S) their `a/` path
+/// prefix marks authenticated access and is not part of the project
+#[derive(Debug, Clone, Default)]
+pub struct GerritHosts {
+    hosts: Vec<String>,
+}
+
+impl GerritHosts {
+    pub fn new(hosts: &[String]) -> Self {
+        Self {
+            hosts: hosts.iter().map(|host| host.to_ascii_lowercase()).collect(),
+        }
+    }
+
+    /// Drop the `a/` prefix from an HTTP(S) URL on a Gerrit host:
+    /// `https://review.example.com/a/path/to/repo` -> `https://review.example.com/path/to/repo`
+    /// Returns `None` for other URLs
+    pub fn resolve_url(&self, url: &str) -> Option<String> {
+        let (scheme, after) = url.split_once("://")?;
+        if !matches!(scheme, "http" | "https") {
+            return None;
+        }
+        let (authority, path) = after.split_at(after.find('/')?);
+        let host_port = authority.rsplit_once('@').map_or(authority, |(_, host_port)| host_port);
+        let host = host_port.split_once(':').map_or(host_port, |(host, _)| host);
+        if !self.hosts.contains(&host.to_ascii_lowercase()) {
+            return None;
+        }
+
+        let project = path.strip_prefix("/a/")?;
+        project
+            .contains('/')
+            .then(|| format!("{}://{}/{}", scheme, authority, project))
+    }
+}
+
+/// Build a repository identity from URL parts, undoing forge-specific path shapes
+fn build_repo_id(
+    protocol: Option<Protocol>,
+    user: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+    path: &str,
+) -> Option<RepoId> {
+    let http = matches!(protocol, Some(Protocol::Http | Protocol::Https));
+    let (host, path) = normalize_forge_path(host, path, http);
+    let (namespace, name) = split_namespace_path(&path)?;
+    Some(RepoId {
+        protocol,
+        user,
+        host,
+        port,
+        namespace,
+        name,
+    })
+}
 
-    /// Manual parsing fallback for edge cases
-    fn manual_parse(&self, url: &str) -> bool {
-        // Try to extract owner/repo from various URL formats
-        // Pattern: .*[:/]owner/repo(.git)?
+/// Public forges whose URL paths are always plain namespace/repo
+const PUBLIC_FORGES: [&str; 3] = ["github.com", "gitlab.com", "bitbucket.org"];
 
-        // Remove common prefixes
-        let url = url
-            .trim_start_matches("https://")
-            .trim_start_matches("http://")
-            .trim_start_matches("ssh://")
-            .trim_start_matches("git@");
+/// Context paths Bitbucket Server is commonly served under, as in `host/bitbucket/scm/PROJ/repo`
+const BITBUCKET_CONTEXTS: [&str; 2] = ["bitbucket", "stash"];
 
-        // Look for owner/repo pattern
-        // Examples:
-        // github.com:owner/repo.git
-        // github.com/owner/repo.git
-        // github.com/owner/repo
+/// Rewrite forge-specific URL shapes into (host, "namespace/repo")
+///
+/// - Azure DevOps: `dev.azure.com/org/project/_git/repo`, `org.visualstudio.com/project/_git/repo`
+///   and `ssh.dev.azure.com:v3/org/project/repo` all become `dev.azure.com` + `org/project/repo`
+/// - Bitbucket Server: `host/scm/PROJ/repo.git` (possibly under a `bitbucket/` or `stash/`
+///   context path) becomes `PROJ/repo`
+///
+/// Gerrit's `a/` prefix is only known per host; see [`GerritHosts`]
+fn normalize_forge_path(host: Option<String>, path: &str, http: bool) -> (Option<String>, String) {
+    let path = path.trim_matches('/');
+    let segments: Vec<&str> = path.split('/').collect();
+    let Some(host_name) = host.as_deref().map(str::to_ascii_lowercase) else {
+        return (host, path.to_string());
+    };
 
-        if let Some(colon_pos) = url.find(':') {
-            // SSH format: github.com:owner/repo
-            let after_colon = &url[colon_pos + 1..];
-            return self.match_owner_repo_path(after_colon);
+    const AZURE: &str = "dev.azure.com";
+
+    // Azure DevOps over SSH: v3/org/project/repo
+    if !http && (host_name == "ssh.dev.azure.com" || host_name == "vs-ssh.visualstudio.com") {
+        if let ["v3", rest @ ..] = segments.as_slice() {
+            return (Some(AZURE.to_string()), rest.join("/"));
         }
+    }
 
-        if let Some(slash_pos) = url.find('/') {
-            // HTTPS format: github.com/owner/repo
-            let after_first_slash = &url[slash_pos + 1..];
-            return self.match_owner_repo_path(after_first_slash);
+    // Azure DevOps over HTTPS: the "_git" marker separates project from repo
+    if http && segments.contains(&"_git") {
+        let without_marker: Vec<&str> = segments.iter().copied().filter(|s| *s != "_git").collect();
+
+        if host_name == AZURE {
+            return (host, without_marker.join("/"));
         }
 
-        false
+        // Legacy org.visualstudio.com/[DefaultCollection/]project/_git/repo
+        if let Some(org) = host_name.strip_suffix(".visualstudio.com") {
+            let project_path = match without_marker.as_slice() {
+                [collection, rest @ ..] if collection.eq_ignore_ascii_case("DefaultCollection") => rest,
+                rest => rest,
+            };
+            return (Some(AZURE.to_string()), format!("{}/{}", org, project_path.join("/")));
+        }
     }
 
-    /// Check if a path segment matches owner/repo pattern
-    fn match_owner_repo_path(&self, path: &str) -> bool {
-        let parts: Vec<&str> = path.split('/').collect();
-        if parts.len() < 2 {
-            return false;
+    // Bitbucket Server: [context/]scm/PROJ/repo; projects are flat, and only the
+    // usual context paths count, so GitLab groups named "scm" are left alone
+    if http && !PUBLIC_FORGES.contains(&host_name.as_str()) {
+        let project = match segments.as_slice() {
+            ["scm", project @ ..] => Some(project),
+            [context, "scm", project @ ..] if BITBUCKET_CONTEXTS.contains(&context.to_ascii_lowercase().as_str()) => {
+                Some(project)
+            }
+            _ => None,
+        };
+        if let Some(project @ [_, _]) = project {
+            return (host, project.join("/"));
         }
+    }
+
+    (host, path.to_string())
+}
 
-        let url_owner = parts[0];
-        let url_repo = self.normalize_repo_name(parts[1]);
+/// Split a repository path into (namespace, repo), stripping any .git suffix
+/// Example: "/group/subgroup/project.git" -> ("group/subgroup", "project")
+fn split_namespace_path(path: &str) -> Option<(String, String)> {
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
 
-        self.owner.eq_ignore_ascii_case(url_owner) && self.repo.eq_ignore_ascii_case(&url_repo)
+    let (namespace, name) = path.rsplit_once('/')?;
+    if name.is_empty() || namespace.split('/').any(|segment| segment.trim().is_empty()) {
+        return None;
     }
+
+    Some((namespace.to_string(), name.to_string()))
+}
+
+/// Split an optional host qualifier off a pattern
+/// Accepts "host:owner/repo" and "host/owner/repo" (host must look like a hostname)
+fn split_host(pattern: &str) -> (Option<&str>, &str) {
+    if let Some((host, path)) = pattern.split_once(':') {
+        return (Some(host.trim()), path);
+    }
+
+    match pattern.split_once('/') {
+        Some((first, rest)) if rest.contains('/') && looks_like_host(first) => (Some(first.trim()), rest),
+        _ => (None, pattern),
+    }
+}
+
+/// Whether a pattern is a clone URL rather than a plain pattern
+/// Catches "scheme://..." as well as scp-like "user@host:path"
+fn is_clone_url(pattern: &str) -> bool {
+    pattern.contains("://") || pattern.split_once(':').is_some_and(|(prefix, _)| prefix.contains('@'))
+}
+
+/// Hostnames are told apart from owners by a dot, e.g. "gitlab.example.com"
+fn looks_like_host(segment: &str) -> bool {
+    segment.contains('.') || segment.eq_ignore_ascii_case("localhost")
+}
+
+/// Whether a pattern segment contains glob metacharacters
+fn is_glob(segment: &str) -> bool {
+    segment.contains(['*', '?', '['])
 }
 
 #[cfg(test)]
@@ -105,17 +627,19 @@ mod tests {
     #[test]
     fn test_pattern_creation() {
         let pattern = RepositoryPattern::new("anthropics/claude-code").unwrap();
-        assert_eq!(pattern.owner, "anthropics");
+        assert_eq!(pattern.namespace, "anthropics");
         assert_eq!(pattern.repo, "claude-code");
     }
 
     #[test]
     fn test_invalid_pattern() {
         assert!(RepositoryPattern::new("invalid").is_err());
-        assert!(RepositoryPattern::new("invalid/foo/bar").is_err());
+        assert!(RepositoryPattern::new("invalid//bar").is_err());
         assert!(RepositoryPattern::new("/").is_err());
         assert!(RepositoryPattern::new("owner/").is_err());
         assert!(RepositoryPattern::new("/repo").is_err());
+        assert!(RepositoryPattern::new("/owner/repo").is_err());
+        assert!(RepositoryPattern::new("owner/repo/").is_err());
     }
 
     #[test]
@@ -152,4 +676,333 @@ mod tests {
         assert!(!pattern.matches("https://github.com/different/repo.git"));
         assert!(!pattern.matches("git@github.com:anthropics/different-repo.git"));
     }
+
+    #[test]
+    fn test_glob_matching() {
+        let org = RepositoryPattern::new("anthropics/*").unwrap();
+        assert!(org.matches("git@github.com:anthropics/claude-code.git"));
+        assert!(org.matches("https://github.com/Anthropics/courses"));
+        assert!(!org.matches("https://github.com/other/claude-code"));
+
+        let family = RepositoryPattern::new("*/claude-*").unwrap();
+        assert!(family.matches("https://github.com/anthropics/claude-code.git"));
+        assert!(family.matches("git@github.com:someone/claude-tools.git"));
+        assert!(!family.matches("git@github.com:anthropics/courses.git"));
+
+        let single = RepositoryPattern::new("anthropics/claude-cod?").unwrap();
+        assert!(single.matches("git@github.com:anthropics/claude-code.git"));
+    }
+
+    #[test]
+    fn test_regex_matching() {
+        let pattern = RepositoryPattern::regex("^myorg/(svc|lib)-.*$").unwrap();
+
+        assert!(pattern.matches("git@github.com:myorg/svc-auth.git"));
+        assert!(pattern.matches("https://gitlab.com/MyOrg/lib-common"));
+        assert!(!pattern.matches("https://github.com/myorg/tool-cli.git"));
+        assert!(!pattern.matches("https://github.com/otherorg/svc-auth.git"));
+
+        assert!(RepositoryPattern::regex("(unclosed").is_err());
+    }
+
+    #[test]
+    fn test_match_remote_reports_concrete_repo() {
+        let pattern = RepositoryPattern::new("anthropics/*").unwrap();
+
+        let matched = pattern.match_remote("git@github.com:anthropics/claude-code.git").unwrap();
+        assert_eq!(matched.path(), "anthropics/claude-code");
+        assert_eq!(matched.to_string(), "github.com/anthropics/claude-code");
+        assert_eq!(pattern.match_remote("git@github.com:other/claude-code.git"), None);
+    }
+
+    #[test]
+    fn test_host_qualified_pattern() {
+        let pattern = RepositoryPattern::new("gitlab.example.com/foo/bar").unwrap();
+        assert_eq!(pattern.host.as_deref(), Some("gitlab.example.com"));
+
+        assert!(pattern.matches("git@gitlab.example.com:foo/bar.git"));
+        assert!(pattern.matches("https://GitLab.Example.com/foo/bar"));
+        assert!(!pattern.matches("git@github.com:foo/bar.git"));
+        assert!(!pattern.matches("https://github.com/foo/bar"));
+
+        let colon = RepositoryPattern::new("github.com:foo/bar").unwrap();
+        assert!(colon.matches("https://github.com/foo/bar.git"));
+        assert!(!colon.matches("git@gitlab.example.com:foo/bar.git"));
+    }
+
+    #[test]
+    fn test_unqualified_pattern_matches_any_host() {
+        let pattern = RepositoryPattern::new("foo/bar").unwrap();
+        assert!(pattern.host.is_none());
+
+        assert!(pattern.matches("git@github.com:foo/bar.git"));
+        assert!(pattern.matches("https://gitlab.example.com/foo/bar.git"));
+    }
+
+    #[test]
+    fn test_invalid_host_pattern() {
+        assert!(RepositoryPattern::new(":foo/bar").is_err());
+        assert!(RepositoryPattern::new("github.com:foo").is_err());
+    }
+
+    #[test]
+    fn test_nested_namespace_pattern() {
+        let pattern = RepositoryPattern::new("platform/infra/terraform/modules").unwrap();
+        assert_eq!(pattern.namespace, "platform/infra/terraform");
+        assert_eq!(pattern.repo, "modules");
+
+        assert!(pattern.matches("git@gitlab.com:platform/infra/terraform/modules.git"));
+        assert!(pattern.matches("https://gitlab.com/platform/infra/terraform/modules"));
+        assert!(pattern.matches("ssh://git@gitlab.com:2222/platform/infra/terraform/modules.git"));
+        assert!(!pattern.matches("https://gitlab.com/platform/infra/modules.git"));
+    }
+
+    #[test]
+    fn test_nested_namespace_is_not_truncated() {
+        let pattern = RepositoryPattern::new("platform/infra").unwrap();
+
+        assert!(pattern.matches("https://gitlab.com/platform/infra.git"));
+        assert!(!pattern.matches("https://gitlab.com/platform/infra/tools.git"));
+        assert!(!pattern.matches("git@gitlab.com:platform/infra/tools.git"));
+
+        let matched = RepositoryPattern::new("platform/infra/tools")
+            .unwrap()
+            .match_remote("git@gitlab.com:platform/infra/tools.git")
+            .unwrap();
+        assert_eq!(matched.namespace, "platform/infra");
+        assert_eq!(matched.name, "tools");
+    }
+
+    #[test]
+    fn test_nested_namespace_glob_and_host() {
+        let group = RepositoryPattern::new("platform/infra/*").unwrap();
+        assert!(group.matches("git@gitlab.com:platform/infra/tools.git"));
+        assert!(!group.matches("git@gitlab.com:platform/infra/terraform/modules.git"));
+
+        let hosted = RepositoryPattern::new("gitlab.example.com/platform/infra/tools").unwrap();
+        assert_eq!(hosted.host.as_deref(), Some("gitlab.example.com"));
+        assert!(hosted.matches("https://gitlab.example.com/platform/infra/tools.git"));
+        assert!(!hosted.matches("https://gitlab.com/platform/infra/tools.git"));
+    }
+
+    #[test]
+    fn test_clone_url_pattern() {
+        let urls = [
+            "git@github.com:anthropics/claude-code.git",
+            "ssh://git@github.com/anthropics/claude-code.git",
+            "ssh://git@github.com:22/anthropics/claude-code",
+            "https://github.com/anthropics/claude-code.git",
+            "git://github.com/anthropics/claude-code.git",
+        ];
+
+        for url in urls {
+            let pattern = RepositoryPattern::new(url).unwrap();
+            assert_eq!(pattern.host.as_deref(), Some("github.com"), "{}", url);
+            assert_eq!(pattern.namespace, "anthropics", "{}", url);
+            assert_eq!(pattern.repo, "claude-code", "{}", url);
+
+            // Protocol-agnostic: every form matches every other form
+            for remote in urls {
+                assert!(pattern.matches(remote), "{} should match {}", url, remote);
+            }
+            assert!(!pattern.matches("https://gitlab.com/anthropics/claude-code.git"));
+        }
+    }
+
+    #[test]
+    fn test_clone_url_pattern_nested_namespace() {
+        let pattern = RepositoryPattern::new("https://gitlab.example.com/group/sub/repo").unwrap();
+
+        assert!(pattern.matches("git@gitlab.example.com:group/sub/repo.git"));
+        assert!(!pattern.matches("git@gitlab.example.com:group/repo.git"));
+        assert!(RepositoryPattern::new("https://github.com/").is_err());
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let seen: Vec<RepoId> = [
+            "git@github.com:anthropics/claude-code.git",
+            "https://github.com/newowner/widgets.git",
+            "https://github.com/unrelated/project.git",
+        ]
+        .iter()
+        .filter_map(|url| RepoId::parse(url))
+        .collect();
+
+        let typo = RepositoryPattern::new("anthropic/claude-code").unwrap();
+        assert_eq!(typo.suggestions(&seen, 3), vec!["anthropics/claude-code"]);
+
+        let moved = RepositoryPattern::new("oldowner/widgets").unwrap();
+        assert_eq!(moved.suggestions(&seen, 3), vec!["newowner/widgets"]);
+
+        let other_host = RepositoryPattern::new("gitlab.com/anthropic/claude-code").unwrap();
+        assert!(other_host.suggestions(&seen, 3).is_empty());
+
+        let glob = RepositoryPattern::new("anthropic/*").unwrap();
+        assert!(glob.suggestions(&seen, 3).is_empty());
+
+        let exact = RepositoryPattern::new("anthropics/claude-code").unwrap();
+        assert!(exact.suggestions(&seen, 3).is_empty());
+    }
+
+    #[test]
+    fn test_forge_normalization() {
+        let cases = [
+            // Azure DevOps
+            ("https://dev.azure.com/org/project/_git/repo", "dev.azure.com/org/project/repo"),
+            ("https://org@dev.azure.com/org/project/_git/repo", "dev.azure.com/org/project/repo"),
+            ("git@ssh.dev.azure.com:v3/org/project/repo", "dev.azure.com/org/project/repo"),
+            ("https://org.visualstudio.com/project/_git/repo", "dev.azure.com/org/project/repo"),
+            (
+                "https://org.visualstudio.com/DefaultCollection/project/_git/repo",
+                "dev.azure.com/org/project/repo",
+            ),
+            ("org@vs-ssh.visualstudio.com:v3/org/project/repo", "dev.azure.com/org/project/repo"),
+            // Bitbucket Server
+            ("https://bitbucket.corp.com/scm/PROJ/repo.git", "bitbucket.corp.com/PROJ/repo"),
+            ("https://corp.com/bitbucket/scm/PROJ/repo.git", "corp.com/PROJ/repo"),
+            ("https://bitbucket.corp.com/scm/~jdoe/repo.git", "bitbucket.corp.com/~jdoe/repo"),
+            ("ssh://git@bitbucket.corp.com:7999/proj/repo.git", "bitbucket.corp.com/proj/repo"),
+            ("https://corp.com/stash/scm/PROJ/repo.git", "corp.com/PROJ/repo"),
+            // Gerrit (the a/ prefix is only dropped for configured hosts, see GerritHosts)
+            ("https://review.example.com/path/to/repo", "review.example.com/path/to/repo"),
+            ("ssh://jdoe@review.example.com:29418/path/to/repo", "review.example.com/path/to/repo"),
+            // Self-hosted GitLab groups named like forge markers keep their full namespace
+            ("https://gitlab.corp.com/platform/scm/tools/repo.git", "gitlab.corp.com/platform/scm/tools/repo"),
+            ("https://gitlab.corp.com/scm/team/sub/repo.git", "gitlab.corp.com/scm/team/sub/repo"),
+            ("https://gitlab.corp.com/a/team/repo.git", "gitlab.corp.com/a/team/repo"),
+            ("https://gitlab.corp.com/a/repo.git", "gitlab.corp.com/a/repo"),
+            // Public forges keep their paths untouched
+            ("https://github.com/a/repo.git", "github.com/a/repo"),
+            ("https://github.com/scm/tools/repo.git", "github.com/scm/tools/repo"),
+            ("https://bitbucket.org/team/repo.git", "bitbucket.org/team/repo"),
+        ];
+
+        for (url, expected) in cases {
+            let id = RepoId::parse(url).unwrap_or_else(|| panic!("failed to parse {}", url));
+            assert_eq!(id.to_string(), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_forge_patterns_match_across_url_shapes() {
+        let azure = RepositoryPattern::new("dev.azure.com/org/project/repo").unwrap();
+        assert!(azure.matches("https://dev.azure.com/org/project/_git/repo"));
+        assert!(azure.matches("git@ssh.dev.azure.com:v3/org/project/repo"));
+        assert!(azure.matches("https://org.visualstudio.com/project/_git/repo"));
+
+        let bitbucket = RepositoryPattern::new("PROJ/repo").unwrap();
+        assert!(bitbucket.matches("https://bitbucket.corp.com/scm/proj/repo.git"));
+        assert!(bitbucket.matches("ssh://git@bitbucket.corp.com:7999/proj/repo.git"));
+
+        let gitlab = RepositoryPattern::new("platform/scm/tools/repo").unwrap();
+        assert!(gitlab.matches("https://gitlab.corp.com/platform/scm/tools/repo.git"));
+        let truncated = RepositoryPattern::new("tools/repo").unwrap();
+        assert!(!truncated.matches("https://gitlab.corp.com/platform/scm/tools/repo.git"));
+    }
+
+    #[test]
+    fn test_gerrit_hosts() {
+        let gerrit = GerritHosts::new(&["Review.example.com".to_string()]);
+
+        assert_eq!(
+            gerrit.resolve_url("https://review.example.com/a/path/to/repo").as_deref(),
+            Some("https://review.example.com/path/to/repo")
+        );
+        assert_eq!(
+            gerrit.resolve_url("https://jdoe@review.example.com:8443/a/path/to/repo").as_deref(),
+            Some("https://jdoe@review.example.com:8443/path/to/repo")
+        );
+        assert_eq!(gerrit.resolve_url("https://review.example.com/path/to/repo"), None);
+        assert_eq!(gerrit.resolve_url("https://gitlab.corp.com/a/team/repo.git"), None);
+        assert_eq!(gerrit.resolve_url("ssh://review.example.com:29418/a/path/to/repo"), None);
+
+        let pattern = RepositoryPattern::new("path/to/repo").unwrap();
+        let url = gerrit.resolve_url("https://review.example.com/a/path/to/repo").unwrap();
+        assert!(pattern.matches(&url));
+    }
+
+    #[test]
+    fn test_fork_matching() {
+        let pattern = RepositoryPattern::new("rust-lang/cargo").unwrap();
+
+        let fork = pattern.match_fork("git@github.com:me/cargo.git").unwrap();
+        assert_eq!(fork.namespace, "me");
+        assert_eq!(pattern.match_fork("git@github.com:rust-lang/cargo.git"), None);
+        assert_eq!(pattern.match_fork("git@github.com:me/other.git"), None);
+
+        let hosted = RepositoryPattern::new("github.com/rust-lang/cargo").unwrap();
+        assert_eq!(hosted.match_fork("git@gitlab.com:me/cargo.git"), None);
+
+        let regex = RepositoryPattern::regex("^rust-lang/cargo$").unwrap();
+        assert_eq!(regex.match_fork("git@github.com:me/cargo.git"), None);
+
+        let owners = RepositoryPattern::new("rust-*/cargo").unwrap();
+        assert!(owners.match_fork("git@github.com:me/cargo.git").is_some());
+        assert_eq!(owners.match_fork("git@github.com:rust-lang/cargo.git"), None);
+
+        let names = RepositoryPattern::new("rust-lang/cargo*").unwrap();
+        assert_eq!(names.match_fork("git@github.com:me/cargo.git"), None);
+        assert_eq!(names.match_fork("git@github.com:me/cargo-edit.git"), None);
+    }
+
+    #[test]
+    fn test_repo_id_parse() {
+        let scp = RepoId::parse("git@github.com:Anthropics/claude-code.git").unwrap();
+        assert_eq!(scp.protocol, Some(Protocol::Ssh));
+        assert_eq!(scp.user.as_deref(), Some("git"));
+        assert_eq!(scp.host.as_deref(), Some("github.com"));
+        assert_eq!(scp.port, None);
+        assert_eq!(scp.canonical(), "github.com/anthropics/claude-code");
+
+        let ssh = RepoId::parse("ssh://git@gitlab.example.com:2222/group/sub/project.git").unwrap();
+        assert_eq!(ssh.protocol, Some(Protocol::Ssh));
+        assert_eq!(ssh.port, Some(2222));
+        assert_eq!(ssh.namespace, "group/sub");
+        assert_eq!(ssh.name, "project");
+
+        let https = RepoId::parse("https://github.com/anthropics/claude-code").unwrap();
+        assert_eq!(https.protocol, Some(Protocol::Https));
+        assert_eq!(https.user, None);
+        assert_eq!(https.canonical(), scp.canonical());
+
+        assert_eq!(RepoId::parse("file:///srv/git/foo.git"), None);
+
+        let json = serde_json::to_value(&scp).unwrap();
+        assert_eq!(json["protocol"], "ssh");
+        assert_eq!(json["namespace"], "Anthropics");
+        assert_eq!(json["canonical"], "github.com/anthropics/claude-code");
+    }
+
+    #[test]
+    fn test_aliases_match_both_names() {
+        let aliases = Arc::new(RepoAliases::new([("OldOrg/oldname", "neworg/newname")]));
+
+        let new_name = RepositoryPattern::new("neworg/newname").unwrap().with_aliases(aliases.clone());
+        assert!(new_name.matches("git@github.com:oldorg/oldname.git"));
+        assert!(new_name.matches("git@github.com:neworg/newname.git"));
+
+        let old_name = RepositoryPattern::new("oldorg/oldname").unwrap().with_aliases(aliases.clone());
+        assert!(old_name.matches("git@github.com:neworg/newname.git"));
+        assert!(!old_name.matches("git@github.com:neworg/other.git"));
+
+        // The reported repository is the one the remote actually uses
+        let matched = new_name.match_remote("https://github.com/oldorg/oldname.git").unwrap();
+        assert_eq!(matched.path(), "oldorg/oldname");
+        assert_eq!(new_name.renamed_to(&matched).as_deref(), Some("neworg/newname"));
+
+        let current = new_name.match_remote("https://github.com/neworg/newname.git").unwrap();
+        assert_eq!(new_name.renamed_to(&current), None);
+    }
+
+    #[test]
+    fn test_alias_chains() {
+        let aliases = Arc::new(RepoAliases::new([("a/first", "b/second"), ("B/Second", "NewOrg/Third")]));
+        let pattern = RepositoryPattern::new("neworg/third").unwrap().with_aliases(aliases);
+
+        assert!(pattern.matches("https://github.com/a/first.git"));
+        let matched = pattern.match_remote("https://github.com/a/first.git").unwrap();
+        // Reported as written in the config, not lowercased
+        assert_eq!(pattern.renamed_to(&matched).as_deref(), Some("NewOrg/Third"));
+    }
 }
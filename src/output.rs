@@ -1,18 +1,163 @@
 use crate::error::Result;
-use crate::scanner::MatchResult;
+use crate::git::{Activity, Direction, RefMatch, RepoStatus, Worktree};
+use crate::identity::{Identity, IdentityViolation};
+use crate::matcher::RepoId;
+use crate::scanner::{MatchResult, MatchedRemote, MatchedSubmodule, Orphan, Role};
 use colored::Colorize;
 use serde::Serialize;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Serialize)]
 struct JsonRepo {
     path: String,
+    /// clone, fork, mirror, remote, superproject or orphan
+    role: String,
+    bare: bool,
+    /// Configured as a `git clone --mirror` copy
+    mirror: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    superproject: Option<JsonSuperproject>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<JsonStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    activity: Option<JsonActivity>,
+    /// Full SHA of the looked-up commit, with --contains or --same-root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    orphan: Option<JsonOrphan>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refs: Option<JsonRefs>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identity: Option<JsonIdentity>,
     remotes: Vec<JsonRemote>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    submodules: Vec<JsonSubmodule>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    worktrees: Vec<JsonWorktree>,
+}
+
+#[derive(Serialize)]
+struct JsonSubmodule {
+    name: String,
+    path: String,
+    url: String,
+    /// Commit pinned by the superproject's HEAD
+    pinned: Option<String>,
+    repo: RepoId,
+}
+
+#[derive(Serialize)]
+struct JsonStatus {
+    /// Current branch, null when HEAD is detached
+    branch: Option<String>,
+    detached: bool,
+    head: Option<String>,
+    dirty: usize,
+    untracked: usize,
+    upstream: Option<String>,
+    ahead: usize,
+    behind: usize,
+    stashes: usize,
+    /// rebase, merge, bisect, cherry-pick, revert or am
+    operation: Option<String>,
+}
+
+/// Effective commit identity, null fields when unset
+#[derive(Serialize)]
+struct JsonIdentity {
+    name: Option<String>,
+    email: Option<String>,
+    violations: Vec<JsonViolation>,
+}
+
+#[derive(Serialize)]
+struct JsonViolation {
+    /// Remote the rule applied through
+    remote: String,
+    /// Host (and owner) the rule covers
+    rule: String,
+    problem: String,
+}
+
+/// An identity violation in the report at the end of the output
+#[derive(Serialize)]
+struct JsonReportedViolation {
+    path: String,
+    #[serde(flatten)]
+    violation: JsonViolation,
+}
+
+/// Refs that met --has-branch, --has-tag and --on-branch
+#[derive(Serialize)]
+struct JsonRefs {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    branches: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    head: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonOrphan {
+    /// Apparent size in bytes, working tree and git dir included, nested repositories excluded
+    size: u64,
+    /// Local-path remotes that no longer exist; empty when there are no remotes
+    missing_remotes: Vec<JsonMissingRemote>,
+}
+
+#[derive(Serialize)]
+struct JsonMissingRemote {
+    name: String,
+    url: String,
+}
+
+/// Unix timestamps in seconds, null when unknown
+#[derive(Serialize)]
+struct JsonActivity {
+    head_commit: Option<i64>,
+    last_checkout: Option<i64>,
+    last_fetch: Option<i64>,
+    /// Latest of the above
+    last_activity: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct JsonSuperproject {
+    path: String,
+    /// Declared in .gitmodules rather than a nested clone
+    submodule: bool,
+}
+
+#[derive(Serialize)]
+struct JsonWorktree {
+    path: String,
+    branch: Option<String>,
+    /// The worktree directory no longer exists
+    missing: bool,
 }
 
 #[derive(Serialize)]
 struct JsonRemote {
     name: String,
+    direction: String,
     url: String,
+    /// Configured URL, only present when insteadOf rewrote it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw_url: Option<String>,
+    /// Local clones the URL was reached through
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    via: Vec<String>,
+    /// Current name, only present when the remote uses a stale one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    renamed_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    host: Option<String>,
+    matched: String,
+    /// Parsed pieces of the URL, so scripts need not re-parse it
+    repo: RepoId,
 }
 
 #[derive(Serialize)]
@@ -20,6 +165,70 @@ struct JsonOutput {
     pattern: String,
     count: usize,
     repositories: Vec<JsonRepo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suggestions: Vec<String>,
+    /// Worktrees whose main repository has disappeared
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    orphaned_worktrees: Vec<String>,
+    /// Repositories breaking identity rules, with --audit-identity
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    identity_violations: Vec<JsonReportedViolation>,
+}
+
+#[derive(Serialize)]
+struct JsonGroupedOutput {
+    /// Distinct repositories matching at least one pattern
+    count: usize,
+    patterns: Vec<JsonOutput>,
+    /// Patterns with no clone at all
+    missing: Vec<String>,
+    /// Worktrees whose main repository has disappeared
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    orphaned_worktrees: Vec<String>,
+    /// Repositories breaking identity rules, with --audit-identity
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    identity_violations: Vec<JsonReportedViolation>,
+}
+
+/// Narrow results down to the repositories (and remotes and submodules) matched by one pattern
+pub fn results_for_pattern(results: &[MatchResult], pattern: usize) -> Vec<MatchResult> {
+    results
+        .iter()
+        .filter_map(|result| {
+            let remotes: Vec<_> = result
+                .remotes
+                .iter()
+                .filter(|remote| remote.pattern == pattern)
+                .cloned()
+                .collect();
+            let submodules: Vec<_> = result
+                .submodules
+                .iter()
+                .filter(|submodule| submodule.pattern == pattern)
+                .cloned()
+                .collect();
+            // Repositories selected without a pattern may have no remote to list
+            let patternless = result.commit.is_some()
+                || result.orphan.is_some()
+                || (result.remotes.is_empty() && result.submodules.is_empty());
+            (!remotes.is_empty() || !submodules.is_empty() || patternless).then(|| MatchResult {
+                path: result.path.clone(),
+                remotes,
+                submodules,
+                worktrees: result.worktrees.clone(),
+                superproject: result.superproject.clone(),
+                status: result.status.clone(),
+                activity: result.activity,
+                commit: result.commit.clone(),
+                orphan: result.orphan.clone(),
+                refs: result.refs.clone(),
+                identity: result.identity.clone(),
+                identity_violations: result.identity_violations.clone(),
+                bare: result.bare,
+                mirror: result.mirror,
+            })
+        })
+        .collect()
 }
 
 /// Print results in human-readable format with colors
@@ -45,44 +254,661 @@ pub fn print_results(results: &[MatchResult], pattern: &str) {
         pattern.cyan()
     );
 
-    for (idx, result) in results.iter().enumerate() {
+    print_repo_list(results);
+}
+
+/// Print results grouped by pattern, followed by the patterns with no clone at all
+pub fn print_grouped_results(results: &[MatchResult], patterns: &[String], suggestions: &[Vec<String>]) {
+    for (idx, pattern) in patterns.iter().enumerate() {
+        let pattern_results = results_for_pattern(results, idx);
+        if pattern_results.is_empty() {
+            continue;
+        }
+
         println!(
-            "{}. {}",
-            (idx + 1).to_string().yellow(),
-            result.path.display().to_string().bold()
+            "{} {} for '{}':\n",
+            pattern_results.len().to_string().green().bold(),
+            if pattern_results.len() == 1 {
+                "repository"
+            } else {
+                "repositories"
+            },
+            pattern.cyan()
         );
+        print_repo_list(&pattern_results);
+    }
+
+    print_missing_patterns(patterns, suggestions, |idx| {
+        results_for_pattern(results, idx).is_empty()
+    });
+}
 
-        for (remote_name, url) in &result.remotes {
-            println!("   {}: {}", remote_name.blue(), url);
+/// Print the end-of-scan summary when matches were already streamed
+pub fn print_summary(results: &[MatchResult], pattern: &str, suggestions: &[String]) {
+    if results.is_empty() {
+        println!(
+            "\n{}",
+            format!("No repositories found matching '{}'", pattern)
+                .yellow()
+                .bold()
+        );
+        print_suggestions(suggestions);
+    } else {
+        println!(
+            "\n{} {} matching '{}'",
+            "Found".green().bold(),
+            if results.len() == 1 {
+                format!("{} repository", results.len())
+            } else {
+                format!("{} repositories", results.len())
+            },
+            pattern.cyan()
+        );
+    }
+}
+
+/// Print the per-pattern summary when matches were already streamed
+pub fn print_grouped_summary(results: &[MatchResult], patterns: &[String], suggestions: &[Vec<String>]) {
+    let counts: Vec<usize> = (0..patterns.len())
+        .map(|idx| results_for_pattern(results, idx).len())
+        .collect();
+
+    println!(
+        "\n{} {} matching {} of {} patterns",
+        "Found".green().bold(),
+        if results.len() == 1 {
+            format!("{} repository", results.len())
+        } else {
+            format!("{} repositories", results.len())
+        },
+        counts.iter().filter(|&&count| count > 0).count(),
+        patterns.len()
+    );
+
+    for (pattern, count) in patterns.iter().zip(&counts) {
+        if *count > 0 {
+            println!("   {}: {}", pattern.cyan(), count);
         }
+    }
+
+    print_missing_patterns(patterns, suggestions, |idx| counts[idx] == 0);
+}
 
+/// Print numbered repositories with their matching remotes
+fn print_repo_list(results: &[MatchResult]) {
+    for (idx, result) in results.iter().enumerate() {
+        println!("{}. {}", (idx + 1).to_string().yellow(), format_repo(result));
         println!();
     }
 }
 
+/// Format a repository as listed: its path and tags, then one indented line
+/// per detail, remote, submodule and worktree
+pub fn format_repo(result: &MatchResult) -> String {
+    let mut lines = vec![format!("{}{}", result.path.display().to_string().bold(), format_tags(result))];
+
+    if let Some(status) = &result.status {
+        lines.push(format_status(status));
+    }
+    if let Some(commit) = &result.commit {
+        lines.push(format_commit(commit));
+    }
+    if let Some(orphan) = &result.orphan {
+        lines.push(format_orphan(orphan));
+    }
+    if let Some(refs) = &result.refs {
+        lines.push(format_refs(refs));
+    }
+    if let Some(identity) = &result.identity {
+        lines.push(format_identity(identity, &result.identity_violations));
+    }
+    if let Some(activity) = &result.activity {
+        lines.push(format_activity(activity));
+    }
+    lines.extend(result.remotes.iter().map(format_remote));
+    lines.extend(result.submodules.iter().map(format_submodule));
+    lines.extend(result.worktrees.iter().map(format_worktree));
+
+    lines.join("\n   ")
+}
+
+/// Tag a repository that is not a plain clone with its role, e.g. " [fork]",
+/// bare repositories with " [bare]" or " [bare mirror]", and vendored ones
+/// with their superproject
+fn format_tags(result: &MatchResult) -> String {
+    let mut tags = Vec::new();
+    if result.role() != Role::Clone {
+        tags.push(result.role().to_string());
+    }
+    if result.bare {
+        tags.push(if result.mirror { "bare mirror" } else { "bare" }.to_string());
+    }
+    if let Some(superproject) = &result.superproject {
+        let relation = if superproject.submodule { "submodule of" } else { "nested in" };
+        tags.push(format!("{} {}", relation, superproject.path.display()));
+    }
+
+    tags.iter().map(|tag| format!(" {}", format!("[{}]", tag).magenta())).collect()
+}
+
+/// Format one matching remote: name, effective URL, matched repo and any rewrite
+fn format_remote(remote: &MatchedRemote) -> String {
+    let label = match remote.direction {
+        Direction::Both => remote.name.blue().to_string(),
+        Direction::Fetch => format!("{} (fetch)", remote.name).blue().to_string(),
+        Direction::Push => format!("{} (push)", remote.name).blue().to_string(),
+    };
+
+    let mut line = format!("{}: {} {}", label, remote.url, format!("({})", remote.matched).dimmed());
+    if remote.raw_url != remote.url {
+        line.push_str(&format!(" {}", format!("[configured as {}]", remote.raw_url).dimmed()));
+    }
+    if let Some(current) = &remote.renamed_to {
+        line.push_str(&format!(" {}", format!("[stale name, now {}]", current).yellow()));
+    }
+    if !remote.via.is_empty() {
+        let chain: Vec<String> = remote.via.iter().map(|p| p.display().to_string()).collect();
+        line.push_str(&format!(" {}", format!("[via {}]", chain.join(" -> ")).dimmed()));
+    }
+    line
+}
+
+/// Format a repository's working state on one line, e.g.
+/// "status: main (ahead 2 of origin/main), 3 dirty, 1 untracked, rebase in progress"
+fn format_status(status: &RepoStatus) -> String {
+    let head = match (&status.branch, &status.head) {
+        (Some(branch), _) => branch.green().to_string(),
+        (None, Some(head)) => format!("detached at {}", &head[..head.len().min(12)]).yellow().to_string(),
+        (None, None) => "no commits".dimmed().to_string(),
+    };
+
+    let mut parts = vec![head];
+    if let Some(upstream) = &status.upstream {
+        let divergence = match (status.ahead, status.behind) {
+            (0, 0) => format!("up to date with {}", upstream),
+            (ahead, 0) => format!("ahead {} of {}", ahead, upstream),
+            (0, behind) => format!("behind {} of {}", behind, upstream),
+            (ahead, behind) => format!("ahead {}, behind {} of {}", ahead, behind, upstream),
+        };
+        parts[0].push_str(&format!(" {}", format!("({})", divergence).dimmed()));
+    }
+    if status.dirty > 0 {
+        parts.push(format!("{} dirty", status.dirty).red().to_string());
+    }
+    if status.untracked > 0 {
+        parts.push(format!("{} untracked", status.untracked));
+    }
+    if status.stashes > 0 {
+        parts.push(format!("{} {}", status.stashes, if status.stashes == 1 { "stash" } else { "stashes" }));
+    }
+    if let Some(operation) = &status.operation {
+        parts.push(format!("{} in progress", operation).red().bold().to_string());
+    }
+
+    format!("{}: {}", "status".blue(), parts.join(", "))
+}
+
+/// Format the looked-up commit a repository contains, e.g. "contains: 1a2b3c4d5e6f"
+fn format_commit(commit: &str) -> String {
+    format!("{}: {}", "contains".blue(), commit[..commit.len().min(12)].yellow())
+}
+
+/// Format a repository's commit identity, flagging the rules it breaks, e.g.
+/// "identity: Jane Doe <jane@personal.dev> [violates gitlab.corp.com]"
+fn format_identity(identity: &Identity, violations: &[IdentityViolation]) -> String {
+    let mut line = format!("{}: {}", "identity".blue(), identity);
+    if !violations.is_empty() {
+        let rules: Vec<&str> = violations.iter().map(|violation| violation.rule.as_str()).collect();
+        line.push_str(&format!(" {}", format!("[violates {}]", rules.join(", ")).red()));
+    }
+    line
+}
+
+/// Format the refs that met the branch and tag filters, e.g.
+/// "refs: on main, branch feature/x, tag v2.1.0"
+fn format_refs(refs: &RefMatch) -> String {
+    let parts: Vec<String> = refs
+        .head
+        .iter()
+        .map(|head| format!("on {}", head.green()))
+        .chain(refs.branches.iter().map(|branch| format!("branch {}", branch)))
+        .chain(refs.tags.iter().map(|tag| format!("tag {}", tag.yellow())))
+        .collect();
+    format!("{}: {}", "refs".blue(), parts.join(", "))
+}
+
+/// Format why a repository is an orphan and its size, e.g.
+/// "orphan: no remotes, 12.4 MiB" or "orphan: origin -> ../old (missing), 3.0 KiB"
+fn format_orphan(orphan: &Orphan) -> String {
+    let reason = if orphan.missing_remotes.is_empty() {
+        "no remotes".red().to_string()
+    } else {
+        orphan
+            .missing_remotes
+            .iter()
+            .map(|remote| format!("{} -> {} {}", remote.name, remote.url, "(missing)".red()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    format!("{}: {}, {}", "orphan".blue(), reason, format_size(orphan.size))
+}
+
+/// Human-readable size in binary units, e.g. "12.4 MiB"
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Format when a repository was last worked on, e.g.
+/// "activity: committed 3 days ago, checked out 2 days ago, never fetched"
+fn format_activity(activity: &Activity) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_secs() as i64);
+    let describe = |what: &str, never: &str, time: Option<i64>| match time {
+        Some(time) => format!("{} {}", what, format_age(now - time)),
+        None => never.dimmed().to_string(),
+    };
+
+    let parts = [
+        describe("committed", "no commits", activity.head_commit),
+        describe("checked out", "never checked out", activity.last_checkout),
+        describe("fetched", "never fetched", activity.last_fetch),
+    ];
+    format!("{}: {}", "activity".blue(), parts.join(", "))
+}
+
+/// Describe how long ago something happened, in the largest whole unit
+fn format_age(seconds: i64) -> String {
+    const UNITS: [(&str, i64); 6] = [
+        ("year", 365 * 86400),
+        ("month", 30 * 86400),
+        ("week", 7 * 86400),
+        ("day", 86400),
+        ("hour", 3600),
+        ("minute", 60),
+    ];
+
+    UNITS
+        .iter()
+        .find(|(_, length)| seconds >= *length)
+        .map_or_else(
+            || "just now".to_string(),
+            |(unit, length)| {
+                let count = seconds / length;
+                format!("{} {}{} ago", count, unit, if count == 1 { "" } else { "s" })
+            },
+        )
+}
+
+/// Format one matching submodule: path, URL, matched repo and pinned commit
+fn format_submodule(submodule: &MatchedSubmodule) -> String {
+    let mut line = format!(
+        "{}: {} {}",
+        format!("submodule {}", submodule.path.display()).blue(),
+        submodule.url,
+        format!("({})", submodule.matched).dimmed()
+    );
+    if let Some(pinned) = &submodule.pinned {
+        line.push_str(&format!(" {}", format!("@ {}", &pinned[..pinned.len().min(12)]).yellow()));
+    }
+    line
+}
+
+/// Format one linked worktree: path, branch and whether it is gone
+fn format_worktree(worktree: &Worktree) -> String {
+    let branch = worktree.branch.as_deref().unwrap_or("detached");
+    let mut line = format!(
+        "{}: {} {}",
+        "worktree".blue(),
+        worktree.path.display(),
+        format!("({})", branch).dimmed()
+    );
+    if worktree.missing {
+        line.push_str(&format!(" {}", "[missing]".yellow()));
+    }
+    line
+}
+
+/// Warn about worktrees whose main repository has disappeared
+pub fn print_orphaned_worktrees(orphaned: &[PathBuf]) {
+    if orphaned.is_empty() {
+        return;
+    }
+
+    println!("\n{}", "Worktrees whose main repository is missing:".yellow().bold());
+    for path in orphaned {
+        println!("   {}", path.display());
+    }
+}
+
+/// Report every repository whose commit identity breaks a rule
+pub fn print_identity_violations(results: &[MatchResult]) {
+    let offenders: Vec<&MatchResult> = results
+        .iter()
+        .filter(|result| !result.identity_violations.is_empty())
+        .collect();
+    if offenders.is_empty() {
+        return;
+    }
+
+    println!("\n{}", "Identity violations:".red().bold());
+    for result in offenders {
+        println!("   {}", result.path.display().to_string().bold());
+        for violation in &result.identity_violations {
+            println!(
+                "      {} {}",
+                violation.problem,
+                format!("({} via {})", violation.rule, violation.remote).dimmed()
+            );
+        }
+    }
+}
+
+/// List patterns that had no clone, with any suggestions inline
+fn print_missing_patterns(patterns: &[String], suggestions: &[Vec<String>], is_missing: impl Fn(usize) -> bool) {
+    let missing: Vec<usize> = (0..patterns.len()).filter(|&idx| is_missing(idx)).collect();
+    if missing.is_empty() {
+        return;
+    }
+
+    println!("\n{}", "No clone found for:".yellow().bold());
+    for idx in missing {
+        match suggestions.get(idx).filter(|s| !s.is_empty()) {
+            Some(close) => println!(
+                "   {} {}",
+                patterns[idx].cyan(),
+                format!("(did you mean {}?)", close.join(", ")).dimmed()
+            ),
+            None => println!("   {}", patterns[idx].cyan()),
+        }
+    }
+}
+
+/// Print "did you mean" suggestions after an empty search
+pub fn print_suggestions(suggestions: &[String]) {
+    if suggestions.is_empty() {
+        return;
+    }
+
+    println!("\n{}", "Did you mean:".bold());
+    for suggestion in suggestions {
+        println!("   {}", suggestion.cyan());
+    }
+}
+
 /// Print results in JSON format
-pub fn print_json(results: &[MatchResult], pattern: &str) -> Result<()> {
-    let json_output = JsonOutput {
+pub fn print_json(
+    results: &[MatchResult],
+    pattern: &str,
+    suggestions: &[String],
+    orphaned: &[PathBuf],
+) -> Result<()> {
+    let mut json_output = json_output(results, pattern, suggestions);
+    json_output.orphaned_worktrees = orphaned.iter().map(|p| p.display().to_string()).collect();
+    json_output.identity_violations = json_violation_report(results);
+
+    let json_str = serde_json::to_string_pretty(&json_output)?;
+    println!("{}", json_str);
+
+    Ok(())
+}
+
+/// Print results grouped by pattern in JSON format
+pub fn print_grouped_json(
+    results: &[MatchResult],
+    patterns: &[String],
+    suggestions: &[Vec<String>],
+    orphaned: &[PathBuf],
+) -> Result<()> {
+    let json_output = grouped_json_output(results, patterns, suggestions, orphaned);
+
+    let json_str = serde_json::to_string_pretty(&json_output)?;
+    println!("{}", json_str);
+
+    Ok(())
+}
+
+fn grouped_json_output(
+    results: &[MatchResult],
+    patterns: &[String],
+    suggestions: &[Vec<String>],
+    orphaned: &[PathBuf],
+) -> JsonGroupedOutput {
+    let groups: Vec<JsonOutput> = patterns
+        .iter()
+        .enumerate()
+        .map(|(idx, pattern)| {
+            json_output(
+                &results_for_pattern(results, idx),
+                pattern,
+                suggestions.get(idx).map_or(&[], Vec::as_slice),
+            )
+        })
+        .collect();
+
+    JsonGroupedOutput {
+        count: results.len(),
+        missing: groups
+            .iter()
+            .filter(|group| group.count == 0)
+            .map(|group| group.pattern.clone())
+            .collect(),
+        patterns: groups,
+        orphaned_worktrees: orphaned.iter().map(|p| p.display().to_string()).collect(),
+        identity_violations: json_violation_report(results),
+    }
+}
+
+fn json_output(results: &[MatchResult], pattern: &str, suggestions: &[String]) -> JsonOutput {
+    JsonOutput {
         pattern: pattern.to_string(),
         count: results.len(),
         repositories: results
             .iter()
             .map(|result| JsonRepo {
                 path: result.path.display().to_string(),
+                role: result.role().to_string(),
+                bare: result.bare,
+                mirror: result.mirror,
+                superproject: result.superproject.as_ref().map(|superproject| JsonSuperproject {
+                    path: superproject.path.display().to_string(),
+                    submodule: superproject.submodule,
+                }),
+                status: result.status.as_ref().map(|status| JsonStatus {
+                    branch: status.branch.clone(),
+                    detached: status.branch.is_none() && status.head.is_some(),
+                    head: status.head.clone(),
+                    dirty: status.dirty,
+                    untracked: status.untracked,
+                    upstream: status.upstream.clone(),
+                    ahead: status.ahead,
+                    behind: status.behind,
+                    stashes: status.stashes,
+                    operation: status.operation.clone(),
+                }),
+                commit: result.commit.clone(),
+                orphan: result.orphan.as_ref().map(|orphan| JsonOrphan {
+                    size: orphan.size,
+                    missing_remotes: orphan
+                        .missing_remotes
+                        .iter()
+                        .map(|remote| JsonMissingRemote {
+                            name: remote.name.clone(),
+                            url: remote.url.clone(),
+                        })
+                        .collect(),
+                }),
+                identity: result.identity.as_ref().map(|identity| JsonIdentity {
+                    name: identity.name.clone(),
+                    email: identity.email.clone(),
+                    violations: result.identity_violations.iter().map(json_violation).collect(),
+                }),
+                refs: result.refs.as_ref().map(|refs| JsonRefs {
+                    branches: refs.branches.clone(),
+                    tags: refs.tags.clone(),
+                    head: refs.head.clone(),
+                }),
+                activity: result.activity.map(|activity| JsonActivity {
+                    head_commit: activity.head_commit,
+                    last_checkout: activity.last_checkout,
+                    last_fetch: activity.last_fetch,
+                    last_activity: activity.latest(),
+                }),
                 remotes: result
                     .remotes
                     .iter()
-                    .map(|(name, url)| JsonRemote {
-                        name: name.clone(),
-                        url: url.clone(),
+                    .map(|remote| JsonRemote {
+                        name: remote.name.clone(),
+                        direction: remote.direction.to_string(),
+                        url: remote.url.clone(),
+                        raw_url: (remote.raw_url != remote.url).then(|| remote.raw_url.clone()),
+                        via: remote.via.iter().map(|p| p.display().to_string()).collect(),
+                        renamed_to: remote.renamed_to.clone(),
+                        host: remote.matched.host.clone(),
+                        matched: remote.matched.path(),
+                        repo: remote.matched.clone(),
+                    })
+                    .collect(),
+                submodules: result
+                    .submodules
+                    .iter()
+                    .map(|submodule| JsonSubmodule {
+                        name: submodule.name.clone(),
+                        path: submodule.path.display().to_string(),
+                        url: submodule.url.clone(),
+                        pinned: submodule.pinned.clone(),
+                        repo: submodule.matched.clone(),
+                    })
+                    .collect(),
+                worktrees: result
+                    .worktrees
+                    .iter()
+                    .map(|worktree| JsonWorktree {
+                        path: worktree.path.display().to_string(),
+                        branch: worktree.branch.clone(),
+                        missing: worktree.missing,
                     })
                     .collect(),
             })
             .collect(),
-    };
+        suggestions: suggestions.to_vec(),
+        orphaned_worktrees: Vec::new(),
+        identity_violations: Vec::new(),
+    }
+}
 
-    let json_str = serde_json::to_string_pretty(&json_output)?;
-    println!("{}", json_str);
+fn json_violation(violation: &IdentityViolation) -> JsonViolation {
+    JsonViolation {
+        remote: violation.remote.clone(),
+        rule: violation.rule.clone(),
+        problem: violation.problem.clone(),
+    }
+}
 
-    Ok(())
+/// Every identity violation of every repository, for the report
+fn json_violation_report(results: &[MatchResult]) -> Vec<JsonReportedViolation> {
+    results
+        .iter()
+        .flat_map(|result| {
+            result.identity_violations.iter().map(|violation| JsonReportedViolation {
+                path: result.path.display().to_string(),
+                violation: json_violation(violation),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A clone at `path` whose origin matched each of the given pattern indices
+    fn result(path: &str, url: &str, patterns: &[usize]) -> MatchResult {
+        let matched = RepoId::parse(url).unwrap();
+        MatchResult {
+            path: PathBuf::from(path),
+            remotes: patterns
+                .iter()
+                .map(|&pattern| MatchedRemote {
+                    name: "origin".to_string(),
+                    direction: Direction::Both,
+                    url: url.to_string(),
+                    raw_url: url.to_string(),
+                    via: Vec::new(),
+                    renamed_to: None,
+                    matched: matched.clone(),
+                    role: Role::Clone,
+                    pattern,
+                })
+                .collect(),
+            worktrees: Vec::new(),
+            submodules: Vec::new(),
+            superproject: None,
+            status: None,
+            activity: None,
+            identity: None,
+            identity_violations: Vec::new(),
+            refs: None,
+            orphan: None,
+            commit: None,
+            bare: false,
+            mirror: false,
+        }
+    }
+
+    fn paths(results: &[MatchResult]) -> Vec<String> {
+        results.iter().map(|result| result.path.display().to_string()).collect()
+    }
+
+    #[test]
+    fn test_results_for_pattern() {
+        // "org/lib" and "org/*" both match the lib clone
+        let results = vec![
+            result("/src/lib", "https://github.com/org/lib.git", &[0, 1]),
+            result("/src/app", "https://github.com/org/app.git", &[1]),
+        ];
+
+        let lib = results_for_pattern(&results, 0);
+        assert_eq!(paths(&lib), vec!["/src/lib"]);
+        assert_eq!(lib[0].remotes.len(), 1);
+        assert_eq!(lib[0].remotes[0].pattern, 0);
+
+        let org = results_for_pattern(&results, 1);
+        assert_eq!(paths(&org), vec!["/src/lib", "/src/app"]);
+        assert!(org.iter().all(|result| result.remotes.len() == 1 && result.remotes[0].pattern == 1));
+
+        assert!(results_for_pattern(&results, 2).is_empty());
+    }
+
+    #[test]
+    fn test_grouped_json_output() {
+        let results = vec![
+            result("/src/lib", "https://github.com/org/lib.git", &[0, 1]),
+            result("/src/app", "https://github.com/org/app.git", &[1]),
+        ];
+        let patterns = ["org/lib", "org/*", "org/tools"].map(str::to_string);
+        let suggestions = vec![Vec::new(), Vec::new(), vec!["org/tool".to_string()]];
+
+        let output = grouped_json_output(&results, &patterns, &suggestions, &[]);
+        // A repository matching two patterns counts once overall, and in both groups
+        assert_eq!(output.count, 2);
+        let counts: Vec<usize> = output.patterns.iter().map(|group| group.count).collect();
+        assert_eq!(counts, vec![1, 2, 0]);
+
+        // A pattern with no hits is listed as missing, with its suggestions
+        assert_eq!(output.missing, vec!["org/tools"]);
+        assert_eq!(output.patterns[2].suggestions, vec!["org/tool"]);
+        assert!(output.patterns[2].repositories.is_empty());
+    }
 }
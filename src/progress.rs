@@ -1,3 +1,4 @@
+use crate::output;
 use crate::scanner::MatchResult;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::path::PathBuf;
@@ -9,7 +10,7 @@ pub enum ProgressMessage {
     /// A directory is being scanned
     ScanningDirectory(PathBuf),
     /// A match was found
-    MatchFound(MatchResult),
+    MatchFound(Box<MatchResult>),
     /// A warning occurred
     Warning(String),
     /// Scanning is complete
@@ -20,7 +21,8 @@ pub enum ProgressMessage {
 pub struct ProgressTracker {
     rx: mpsc::UnboundedReceiver<ProgressMessage>,
     progress_bar: Option<ProgressBar>,
-    show_progress: bool,
+    /// Print matches as they are found; off when they are listed sorted at the end
+    stream_matches: bool,
     verbose_level: u8,
     pattern: String,
 }
@@ -29,6 +31,7 @@ impl ProgressTracker {
     pub fn new(
         rx: mpsc::UnboundedReceiver<ProgressMessage>,
         show_progress: bool,
+        stream_matches: bool,
         verbose_level: u8,
         pattern: String,
     ) -> Self {
@@ -49,7 +52,7 @@ impl ProgressTracker {
         Self {
             rx,
             progress_bar,
-            show_progress,
+            stream_matches,
             verbose_level,
             pattern,
         }
@@ -87,11 +90,11 @@ impl ProgressTracker {
                 ProgressMessage::MatchFound(result) => {
                     // Print match immediately (streaming output)
                     // Always show matches when progress bar is enabled
-                    if let Some(pb) = &self.progress_bar {
+                    if let Some(pb) = self.progress_bar.as_ref().filter(|_| self.stream_matches) {
                         pb.println(self.format_match(&result, matches.len() + 1));
                     }
 
-                    matches.push(result);
+                    matches.push(*result);
 
                     // Update progress bar
                     if let Some(pb) = &self.progress_bar {
@@ -121,7 +124,8 @@ impl ProgressTracker {
         // Finish progress bar
         if let Some(pb) = &self.progress_bar {
             pb.finish_with_message(format!(
-                "Scan complete: {} directories scanned, {} matches found",
+                "Scan complete for '{}': {} directories scanned, {} matches found",
+                self.pattern,
                 dirs_scanned,
                 matches.len()
             ));
@@ -134,16 +138,6 @@ impl ProgressTracker {
     fn format_match(&self, result: &MatchResult, index: usize) -> String {
         use colored::Colorize;
 
-        let mut output = format!(
-            "\n{}. {}",
-            index.to_string().yellow(),
-            result.path.display().to_string().bold()
-        );
-
-        for (remote_name, url) in &result.remotes {
-            output.push_str(&format!("\n   {}: {}", remote_name.blue(), url));
-        }
-
-        output
+        format!("\n{}. {}", index.to_string().yellow(), output::format_repo(result))
     }
 }
@@ -1,48 +1,365 @@
 use crate::error::{FsgitError, Result};
-use crate::git;
-use crate::matcher::RepositoryPattern;
+use crate::git::{
+    self, Activity, ActivityWindow, Direction, MatchOn, RefFilter, RefMatch, RemoteUrl, RepoStatus, Worktree,
+    WorktreeLink,
+};
+use crate::identity::{self, Identity, IdentityRule, IdentityViolation};
+use crate::matcher::{GerritHosts, RepoId, RepositoryPattern};
 use crate::progress::ProgressMessage;
-use std::path::PathBuf;
+use crate::ssh_config::SshConfig;
+use git2::Repository;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::fs;
 use tokio::sync::{mpsc, Mutex, Semaphore};
 
 #[derive(Debug, Clone)]
 pub struct MatchResult {
+    /// Repository path; the main working tree for repositories with linked worktrees
     pub path: PathBuf,
-    pub remotes: Vec<(String, String)>,
+    pub remotes: Vec<MatchedRemote>,
+    /// Linked worktrees of the repository
+    pub worktrees: Vec<Worktree>,
+    /// Submodules whose URL matched, with `--superprojects`
+    pub submodules: Vec<MatchedSubmodule>,
+    /// Repository whose working tree contains this one, with `--nested` or `--submodules`
+    pub superproject: Option<Superproject>,
+    /// Working state of the repository, with `--status`
+    pub status: Option<RepoStatus>,
+    /// Last commit, checkout and fetch times, when recorded
+    pub activity: Option<Activity>,
+    /// Effective commit identity, with `--audit-identity`
+    pub identity: Option<Identity>,
+    /// Identity rules the repository breaks, with `--audit-identity`
+    pub identity_violations: Vec<IdentityViolation>,
+    /// Branches, tags and HEAD that met `--has-branch`, `--has-tag` and `--on-branch`
+    pub refs: Option<RefMatch>,
+    /// Why the repository has no backup, with `--orphans`
+    pub orphan: Option<Orphan>,
+    /// Full SHA of the looked-up commit found in the object database, with `--contains` or `--same-root`
+    pub commit: Option<String>,
+    /// The repository has no working tree
+    pub bare: bool,
+    /// A remote is configured with `mirror = true`, as `git clone --mirror` does
+    pub mirror: bool,
+}
+
+impl MatchResult {
+    /// Closest relationship of the repository to what its remotes (or submodules) matched
+    pub fn role(&self) -> Role {
+        match self.remotes.iter().map(|remote| remote.role).min() {
+            Some(role) => role,
+            None if !self.submodules.is_empty() => Role::Superproject,
+            None if self.orphan.is_some() => Role::Orphan,
+            // Found by commit or ref filters, so the repository itself is a copy even without remotes
+            None => Role::Clone,
+        }
+    }
+}
+
+/// A submodule whose URL matched one of the search patterns
+#[derive(Debug, Clone)]
+pub struct MatchedSubmodule {
+    pub name: String,
+    /// Path of the submodule inside the superproject
+    pub path: PathBuf,
+    pub url: String,
+    /// Commit the superproject's HEAD pins the submodule to
+    pub pinned: Option<String>,
+    /// Concrete repository the URL resolved to
+    pub matched: RepoId,
+    /// Index of the pattern that matched, in the order given to the scanner
+    pub pattern: usize,
+}
+
+/// A repository with no remote to push its work to
+#[derive(Debug, Clone)]
+pub struct Orphan {
+    /// Local-path remotes whose target no longer exists; empty when it has no remotes at all
+    pub missing_remotes: Vec<RemoteUrl>,
+    /// Apparent size in bytes, working tree and git dir included, nested repositories excluded
+    pub size: u64,
+}
+
+/// A repository found inside another repository's working tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Superproject {
+    pub path: PathBuf,
+    /// Declared in the superproject's `.gitmodules`; otherwise a nested clone
+    pub submodule: bool,
+}
+
+/// How a repository relates to the repository a remote matched,
+/// judged by its primary remote ("origin", or else its first fetch remote)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    /// The primary remote is the matched repository
+    Clone,
+    /// The primary remote is a same-named repository under another owner on the same host
+    Fork,
+    /// The primary remote is a same-named repository on another host
+    Mirror,
+    /// The matched repository is only an additional remote
+    Remote,
+    /// The matched repository is one of its submodules
+    Superproject,
+    /// No remote exists to back the repository up
+    Orphan,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Role::Clone => write!(f, "clone"),
+            Role::Fork => write!(f, "fork"),
+            Role::Mirror => write!(f, "mirror"),
+            Role::Remote => write!(f, "remote"),
+            Role::Superproject => write!(f, "superproject"),
+            Role::Orphan => write!(f, "orphan"),
+        }
+    }
+}
+
+/// A remote whose URL matched one of the search patterns
+/// A remote matching several patterns is listed once per pattern
+#[derive(Debug, Clone)]
+pub struct MatchedRemote {
+    pub name: String,
+    pub direction: Direction,
+    /// Effective URL the pattern was matched against, after insteadOf
+    /// rewrites and SSH Host alias resolution
+    pub url: String,
+    /// URL as configured, before any rewrites
+    pub raw_url: String,
+    /// Local clones this URL was reached through, when the remote is a local path
+    pub via: Vec<PathBuf>,
+    /// Current name of the repository when the remote still uses an old one
+    pub renamed_to: Option<String>,
+    /// Concrete repository the URL resolved to
+    pub matched: RepoId,
+    /// How the repository relates to `matched`
+    pub role: Role,
+    /// Index of the pattern that matched, in the order given to the scanner
+    pub pattern: usize,
 }
 
 pub struct Scanner {
     search_path: PathBuf,
-    pattern: RepositoryPattern,
+    patterns: Vec<RepositoryPattern>,
+    /// Remotes matching any of these are never reported
+    excludes: Arc<Vec<RepositoryPattern>>,
+    /// Which remote URLs are matched against the patterns
+    match_on: MatchOn,
+    /// Also match same-named repositories under other owners
+    forks: bool,
+    /// List worktrees outside the scanned tree too
+    all_worktrees: bool,
+    /// Keep scanning the working trees of repositories
+    nested: bool,
+    /// Inspect initialized submodules of repositories
+    submodules: bool,
+    /// Match submodule URLs instead of remotes
+    superprojects: bool,
+    /// Read the working state of matched repositories
+    status: bool,
+    /// Record commit, checkout and fetch times of matched repositories
+    activity: bool,
+    /// Drop repositories whose latest activity falls outside this window
+    activity_window: ActivityWindow,
+    /// Match repositories containing any of these commits instead of matching remotes
+    commits: Arc<Vec<String>>,
+    /// Match repositories without a usable remote instead of matching remotes
+    orphans: bool,
+    /// Branch and tag conditions a matched repository must also meet
+    ref_filter: Arc<RefFilter>,
+    /// Read the commit identity of matched repositories
+    identity_audit: bool,
+    /// Identity rules checked against every remote of audited repositories
+    identity_rules: Arc<Vec<IdentityRule>>,
     max_concurrent: usize,
     verbose: u8,
+    /// Maps SSH Host aliases in remotes to real hostnames
+    ssh_config: Arc<SshConfig>,
+    /// Hosts whose URLs carry Gerrit's `a/` prefix
+    gerrit_hosts: Arc<GerritHosts>,
+    /// Every repository identity seen in a remote that could be reported, matching or not
+    seen_repos: Arc<Mutex<HashSet<RepoId>>>,
+    /// Canonical search path, to tell which worktrees lie inside it
+    search_root: PathBuf,
+    /// Main repositories outside the tree already checked through a worktree
+    checked_mains: Arc<Mutex<HashSet<PathBuf>>>,
+    /// Worktrees found whose main repository no longer exists
+    orphaned_worktrees: Arc<Mutex<Vec<PathBuf>>>,
 }
 
 impl Scanner {
     pub fn new(
         search_path: PathBuf,
-        pattern: RepositoryPattern,
+        patterns: Vec<RepositoryPattern>,
         max_concurrent: usize,
         verbose: u8,
+        ssh_config: SshConfig,
     ) -> Self {
         Self {
+            search_root: search_path.canonicalize().unwrap_or_else(|_| search_path.clone()),
             search_path,
-            pattern,
+            patterns,
+            excludes: Arc::new(Vec::new()),
+            match_on: MatchOn::default(),
+            forks: false,
+            all_worktrees: false,
+            nested: false,
+            submodules: false,
+            superprojects: false,
+            status: false,
+            activity: false,
+            activity_window: ActivityWindow::default(),
+            commits: Arc::new(Vec::new()),
+            orphans: false,
+            ref_filter: Arc::new(RefFilter::default()),
+            identity_audit: false,
+            identity_rules: Arc::new(Vec::new()),
             max_concurrent,
             verbose,
+            ssh_config: Arc::new(ssh_config),
+            gerrit_hosts: Arc::new(GerritHosts::default()),
+            seen_repos: Arc::new(Mutex::new(HashSet::new())),
+            checked_mains: Arc::new(Mutex::new(HashSet::new())),
+            orphaned_worktrees: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
+    /// Drop the `a/` prefix Gerrit puts in front of projects in authenticated URLs on these hosts
+    pub fn with_gerrit_hosts(mut self, gerrit_hosts: GerritHosts) -> Self {
+        self.gerrit_hosts = Arc::new(gerrit_hosts);
+        self
+    }
+
+    /// Drop remotes matching any of these patterns, whatever else they match
+    pub fn with_excludes(mut self, excludes: Vec<RepositoryPattern>) -> Self {
+        self.excludes = Arc::new(excludes);
+        self
+    }
+
+    /// Only match remote URLs used in the given direction
+    pub fn with_match_on(mut self, match_on: MatchOn) -> Self {
+        self.match_on = match_on;
+        self
+    }
+
+    /// Also match remotes with a pattern's repository name under another owner,
+    /// such as a fork's origin, in repositories where another remote matches the pattern
+    pub fn with_forks(mut self, forks: bool) -> Self {
+        self.forks = forks;
+        self
+    }
+
+    /// List every linked worktree of a matched repository, even outside the scanned tree
+    pub fn with_all_worktrees(mut self, all_worktrees: bool) -> Self {
+        self.all_worktrees = all_worktrees;
+        self
+    }
+
+    /// Keep scanning inside repositories' working trees (never their .git dirs)
+    pub fn with_nested(mut self, nested: bool) -> Self {
+        self.nested = nested;
+        self
+    }
+
+    /// Inspect the initialized submodules of every repository found
+    pub fn with_submodules(mut self, submodules: bool) -> Self {
+        self.submodules = submodules;
+        self
+    }
+
+    /// Match patterns against the submodule URLs of each repository instead of
+    /// its remotes, to find superprojects that include a repository
+    pub fn with_superprojects(mut self, superprojects: bool) -> Self {
+        self.superprojects = superprojects;
+        self
+    }
+
+    /// Read branch, changes, upstream divergence, stashes and in-progress
+    /// operations of every matched repository
+    pub fn with_status(mut self, status: bool) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Record when each matched repository last had a commit, checkout and fetch
+    pub fn with_activity(mut self, activity: bool) -> Self {
+        self.activity = activity;
+        self
+    }
+
+    /// Only report repositories with a commit, checkout or fetch within `age`
+    pub fn with_active_since(mut self, age: Option<Duration>) -> Self {
+        self.activity_window.active_since = age;
+        self
+    }
+
+    /// Only report repositories with no commit, checkout or fetch within `age`,
+    /// including those with no recorded activity at all
+    pub fn with_stale_for(mut self, age: Option<Duration>) -> Self {
+        self.activity_window.stale_for = age;
+        self
+    }
+
+    /// Match repositories whose object database contains any of these commits
+    /// (full or abbreviated SHAs) instead of matching remotes against patterns
+    pub fn with_commits(mut self, commits: Vec<String>) -> Self {
+        self.commits = Arc::new(commits);
+        self
+    }
+
+    /// Match repositories with no remotes, or whose remotes are all local paths
+    /// that no longer exist, instead of matching remotes against patterns
+    pub fn with_orphans(mut self, orphans: bool) -> Self {
+        self.orphans = orphans;
+        self
+    }
+
+    /// Only report repositories whose branches, tags and HEAD also meet `filter`
+    pub fn with_ref_filter(mut self, filter: RefFilter) -> Self {
+        self.ref_filter = Arc::new(filter);
+        self
+    }
+
+    /// Read the effective `user.name` and `user.email` of every matched repository
+    /// and check them against the identity rules
+    pub fn with_identity_audit(mut self, audit: bool) -> Self {
+        self.identity_audit = audit;
+        self
+    }
+
+    /// Rules such as "remotes on gitlab.corp.com must use *@corp.com", for the identity audit
+    pub fn with_identity_rules(mut self, rules: Vec<IdentityRule>) -> Self {
+        self.identity_rules = Arc::new(rules);
+        self
+    }
+
+    /// Repository identities read from remotes during the last scan
+    pub async fn seen_repos(&self) -> Vec<RepoId> {
+        self.seen_repos.lock().await.iter().cloned().collect()
+    }
+
+    /// Worktrees found during the last scan whose main repository has disappeared
+    pub async fn orphaned_worktrees(&self) -> Vec<PathBuf> {
+        self.orphaned_worktrees.lock().await.clone()
+    }
+
     /// Perform the async scan for matching repositories with progress tracking
+    /// The tree is walked once no matter how many patterns are searched for
     pub async fn scan(
         &self,
         progress_tx: Option<mpsc::UnboundedSender<ProgressMessage>>,
     ) -> Result<Vec<MatchResult>> {
         let results = Arc::new(Mutex::new(Vec::new()));
         let semaphore = Arc::new(Semaphore::new(self.max_concurrent));
-        let pattern = Arc::new(self.pattern.clone());
+        let patterns = Arc::new(self.patterns.clone());
 
         let progress_tx = Arc::new(progress_tx);
 
@@ -51,17 +368,14 @@ impl Scanner {
             self.search_path.clone(),
             results.clone(),
             semaphore.clone(),
-            pattern.clone(),
+            patterns.clone(),
             progress_tx.clone(),
         )
         .await??;
 
         // Extract results from Arc<Mutex<>>
         let final_results = Arc::try_unwrap(results)
-            .map_err(|_| FsgitError::Io(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Failed to unwrap results",
-            )))?
+            .map_err(|_| FsgitError::Io(std::io::Error::other("Failed to unwrap results")))?
             .into_inner();
 
         Ok(final_results)
@@ -73,7 +387,7 @@ impl Scanner {
         path: PathBuf,
         results: Arc<Mutex<Vec<MatchResult>>>,
         semaphore: Arc<Semaphore>,
-        pattern: Arc<RepositoryPattern>,
+        patterns: Arc<Vec<RepositoryPattern>>,
         progress_tx: Arc<Option<mpsc::UnboundedSender<ProgressMessage>>>,
     ) -> tokio::task::JoinHandle<Result<()>> {
         let verbose = self.verbose;
@@ -87,10 +401,7 @@ impl Scanner {
 
             // Acquire semaphore permit for bounded concurrency
             let _permit = semaphore.acquire().await.map_err(|_| {
-                FsgitError::Io(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Failed to acquire semaphore permit",
-                ))
+                FsgitError::Io(std::io::Error::other("Failed to acquire semaphore permit"))
             })?;
 
             // Try to read directory
@@ -112,66 +423,73 @@ impl Scanner {
             };
 
             let mut subdirs = Vec::new();
+            let mut repo_found = false;
+            let (mut has_head, mut has_objects, mut has_refs) = (false, false, false);
 
             // First pass: collect entries and check for .git directory
             while let Some(entry) = entries.next_entry().await? {
                 let entry_path = entry.path();
                 let file_name = entry.file_name();
 
-                // Check if this is a .git directory
+                // Check if this is a .git directory (or a linked worktree's .git file)
                 if file_name == ".git" {
-                    // This is a git repository - check if it matches our pattern
-                    if let Ok(remotes) = git::get_remote_urls(&path).await {
-                        // Check if any remote matches the pattern
-                        let matching_remotes: Vec<(String, String)> = remotes
-                            .iter()
-                            .filter(|(_, url)| pattern.matches(url))
-                            .cloned()
-                            .collect();
-
-                        if !matching_remotes.is_empty() {
-                            // This repo matches!
-                            let match_result = MatchResult {
-                                path: path.clone(),
-                                remotes: matching_remotes,
-                            };
-
-                            // Send progress update for the match
-                            if let Some(tx) = progress_tx.as_ref() {
-                                let _ = tx.send(ProgressMessage::MatchFound(match_result.clone()));
-                            }
+                    repo_found = true;
 
-                            // Add to results
-                            let mut results_guard = results.lock().await;
-                            results_guard.push(match_result);
-                        }
-                    } else {
-                        if let Some(tx) = progress_tx.as_ref() {
-                            let _ = tx.send(ProgressMessage::Warning(format!(
-                                "Warning: Failed to read remotes from git repo at {}",
-                                path.display()
-                            )));
-                        } else if verbose >= 1 {
-                            eprintln!(
-                                "Warning: Failed to read remotes from git repo at {}",
-                                path.display()
-                            );
+                    // Linked worktrees are reported under their main repository
+                    let repo_path = match git::linked_worktree(&path).await {
+                        Some(link) => scanner.worktree_main(&path, link, &progress_tx).await,
+                        None => Some(path.clone()),
+                    };
+
+                    // This is a git repository - check if it matches our pattern
+                    if let Some(repo_path) = repo_path {
+                        let checked = scanner.check_repository(repo_path, &patterns, &progress_tx).await;
+                        if let Some(match_result) = checked {
+                            scanner.record_match(match_result, &results, &progress_tx).await;
                         }
                     }
 
-                    // CRITICAL: Return early - don't scan subdirectories of git repos
-                    return Ok(());
+                    // With --nested keep scanning the working tree, but never the .git dir
+                    if scanner.nested {
+                        continue;
+                    }
+
+                    // CRITICAL: don't scan subdirectories of git repos, only their
+                    // initialized submodules when asked to
+                    subdirs.clear();
+                    if scanner.submodules {
+                        for submodule in git::submodule_paths(&path).await {
+                            if fs::metadata(submodule.join(".git")).await.is_ok() {
+                                subdirs.push(submodule);
+                            }
+                        }
+                    }
+                    break;
                 }
 
-                // Collect subdirectories for later scanning
+                // Collect subdirectories for later scanning, noting bare repository markers
                 if let Ok(file_type) = entry.file_type().await {
                     if file_type.is_dir() {
+                        has_objects |= file_name == "objects";
+                        has_refs |= file_name == "refs";
                         subdirs.push(entry_path);
+                    } else if file_type.is_file() {
+                        has_head |= file_name == "HEAD";
                     }
                 }
             }
 
-            // Only scan subdirectories if NO .git was found (we return early when .git is found)
+            // A bare repository (e.g. `git clone --mirror`) keeps HEAD, objects/ and refs/ at its root
+            if !repo_found && has_head && has_objects && has_refs {
+                if let Some(match_result) = scanner.check_repository(path.clone(), &patterns, &progress_tx).await {
+                    scanner.record_match(match_result, &results, &progress_tx).await;
+                }
+
+                // Never descend into objects/ and refs/
+                return Ok(());
+            }
+
+            // Scan subdirectories: a plain directory's, or a repository's with --nested / --submodules
             let mut tasks = Vec::new();
 
             for subdir in subdirs {
@@ -179,7 +497,7 @@ impl Scanner {
                     subdir,
                     results.clone(),
                     semaphore.clone(),
-                    pattern.clone(),
+                    patterns.clone(),
                     progress_tx.clone(),
                 );
                 tasks.push(task);
@@ -202,6 +520,471 @@ impl Scanner {
             Ok(())
         })
     }
+
+    /// Read a repository's remotes and match them against every pattern
+    /// Returns the result when any remote matches, or with no patterns whenever the
+    /// repository passes the commit, orphan and ref checks
+    async fn check_repository(
+        &self,
+        repo_path: PathBuf,
+        patterns: &[RepositoryPattern],
+        progress_tx: &Option<mpsc::UnboundedSender<ProgressMessage>>,
+    ) -> Option<MatchResult> {
+        let Ok((repo, mut remotes)) = git::open_repository(&repo_path).await else {
+            self.warn(
+                progress_tx,
+                format!("Warning: Failed to read remotes from git repo at {}", repo_path.display()),
+            );
+            return None;
+        };
+
+        // Resolve SSH Host aliases (e.g. git@github-work:org/repo) and Gerrit prefixes
+        for remote in &mut remotes {
+            if let Some(url) = self.resolve_url(&remote.url) {
+                remote.url = url;
+            }
+        }
+
+        // Remember what we saw for "did you mean" suggestions, leaving out
+        // remotes --not and --match-on keep from ever being reported
+        self.seen_repos.lock().await.extend(
+            self.candidate_remotes(&remotes)
+                .into_iter()
+                .filter_map(|remote| RepoId::parse(&remote.url)),
+        );
+
+        // A commit lookup, --orphans or ref filters alone replace pattern matching;
+        // all remotes are then listed as context
+        let mut commit = None;
+        let mut orphan = None;
+        let (repo, matching_remotes, matching_submodules) = if self.orphans {
+            orphan = Some(orphan_of(&repo_path, &remotes).await?);
+            (repo, Vec::new(), Vec::new())
+        } else if !self.commits.is_empty() {
+            let context = self.context_remotes(&remotes)?;
+            let (repo, found) = self.find_commit(repo, &repo_path, progress_tx).await?;
+            commit = Some(found);
+            (repo, context, Vec::new())
+        } else if self.superprojects {
+            // With --superprojects only submodule URLs are matched
+            let (repo, submodules) = self.match_submodules(repo, &repo_path, patterns, progress_tx).await?;
+            (repo, Vec::new(), submodules)
+        } else if patterns.is_empty() {
+            (repo, self.context_remotes(&remotes)?, Vec::new())
+        } else {
+            (repo, self.match_remotes(&remotes, patterns), Vec::new())
+        };
+
+        if !patterns.is_empty() && matching_remotes.is_empty() && matching_submodules.is_empty() {
+            return None;
+        }
+
+        // Everything else is read in one pass over the open repository; branch, tag
+        // and activity conditions come first and drop the repository before the rest
+        let inspection = git::Inspection {
+            ref_filter: self.ref_filter.clone(),
+            activity: self.activity,
+            window: self.activity_window,
+            status: self.status,
+            identity: self.identity_audit,
+        };
+        let details = match git::inspect(repo, inspection).await {
+            Ok(details) => details,
+            Err(e) => {
+                self.warn(
+                    progress_tx,
+                    format!("Warning: Failed to inspect {}: {}", repo_path.display(), e),
+                );
+                return None;
+            }
+        };
+        for (what, e) in &details.failures {
+            self.warn(
+                progress_tx,
+                format!("Warning: Failed to read {} of {}: {}", what, repo_path.display(), e),
+            );
+        }
+        if details.rejected {
+            return None;
+        }
+
+        // Linked worktrees inside the scanned tree, or all of them with --worktrees
+        let worktrees = details
+            .worktrees
+            .into_iter()
+            .filter(|worktree| self.all_worktrees || self.in_search_root(&worktree.path))
+            .collect();
+
+        // Report what the repository is vendored in
+        let superproject = if self.nested || self.submodules {
+            superproject_of(&repo_path, &self.search_root).await
+        } else {
+            None
+        };
+
+        // Rules apply through any remote, matching the patterns or not
+        let identity_violations = details
+            .identity
+            .as_ref()
+            .map(|identity| identity::audit(identity, &remotes, &self.identity_rules))
+            .unwrap_or_default();
+
+        Some(MatchResult {
+            status: details.status,
+            identity: details.identity,
+            identity_violations,
+            activity: details.activity,
+            commit,
+            orphan,
+            refs: details.refs,
+            submodules: matching_submodules,
+            superproject,
+            // Without a .git entry the repository itself is the git dir
+            bare: !repo_path.join(".git").exists(),
+            mirror: remotes.iter().any(|remote| remote.mirror),
+            path: repo_path,
+            remotes: matching_remotes,
+            worktrees,
+        })
+    }
+
+    /// Rewrite a URL through SSH Host aliases and Gerrit hosts; `None` when neither applies
+    fn resolve_url(&self, url: &str) -> Option<String> {
+        let ssh = self.ssh_config.resolve_url(url);
+        self.gerrit_hosts.resolve_url(ssh.as_deref().unwrap_or(url)).or(ssh)
+    }
+
+    /// Remotes `--match-on` accepts and no exclusion matches
+    fn candidate_remotes<'a>(&self, remotes: &'a [RemoteUrl]) -> Vec<&'a RemoteUrl> {
+        remotes
+            .iter()
+            .filter(|remote| self.match_on.accepts(remote.direction))
+            .filter(|remote| !self.excludes.iter().any(|exclude| exclude.matches(&remote.url)))
+            .collect()
+    }
+
+    /// Check every remote against every pattern, skipping excluded ones
+    fn match_remotes(&self, remotes: &[RemoteUrl], patterns: &[RepositoryPattern]) -> Vec<MatchedRemote> {
+        // Roles are judged against the repository's primary remote
+        let primary = primary_remote(remotes);
+
+        let candidates = self.candidate_remotes(remotes);
+
+        // A same-named repository under another owner is only a fork when
+        // another remote of the repository matches the pattern itself
+        let fork_evidence: Vec<bool> = patterns
+            .iter()
+            .map(|pattern| self.forks && candidates.iter().any(|remote| pattern.matches(&remote.url)))
+            .collect();
+
+        let mut matching_remotes = Vec::new();
+        for remote in candidates {
+            for (idx, pattern) in patterns.iter().enumerate() {
+                let (matched, role) = if let Some(matched) = pattern.match_remote(&remote.url) {
+                    let role = classify(remote, &matched, primary.as_ref());
+                    (matched, role)
+                } else if !fork_evidence[idx] {
+                    continue;
+                } else if let Some(fork) = pattern.match_fork(&remote.url) {
+                    (fork, Role::Fork)
+                } else {
+                    continue;
+                };
+
+                let renamed_to = pattern.renamed_to(&matched);
+                matching_remotes.push(MatchedRemote {
+                    name: remote.name.clone(),
+                    direction: remote.direction,
+                    url: remote.url.clone(),
+                    raw_url: remote.raw_url.clone(),
+                    via: remote.via.clone(),
+                    renamed_to,
+                    matched,
+                    role,
+                    pattern: idx,
+                });
+            }
+        }
+
+        matching_remotes
+    }
+
+    /// The first of the looked-up commits present in a repository's object database
+    /// The repository is handed back for the rest of the checks
+    async fn find_commit(
+        &self,
+        repo: Repository,
+        repo_path: &Path,
+        progress_tx: &Option<mpsc::UnboundedSender<ProgressMessage>>,
+    ) -> Option<(Repository, String)> {
+        let commits = self.commits.clone();
+        let lookup = git::run_blocking(repo, move |repo| {
+            commits
+                .iter()
+                .find_map(|sha| git::find_commit(repo, sha).map_err(|e| (sha.clone(), e)).transpose())
+        })
+        .await;
+
+        match lookup {
+            Ok((repo, Some(Ok(commit)))) => Some((repo, commit)),
+            Ok((_, None)) => None,
+            Ok((_, Some(Err((sha, e))))) => {
+                self.warn(
+                    progress_tx,
+                    format!("Warning: Failed to look up {} in {}: {}", sha, repo_path.display(), e),
+                );
+                None
+            }
+            Err(e) => {
+                self.warn(
+                    progress_tx,
+                    format!("Warning: Failed to look up commits in {}: {}", repo_path.display(), e),
+                );
+                None
+            }
+        }
+    }
+
+    /// Every candidate remote with a recognizable URL, reported against itself,
+    /// for repositories selected by something other than their remotes
+    /// `None` when exclusions leave none of the remotes the repository has,
+    /// just as pattern matching then reports nothing
+    fn context_remotes(&self, remotes: &[RemoteUrl]) -> Option<Vec<MatchedRemote>> {
+        let primary = primary_remote(remotes);
+
+        let candidates = self.candidate_remotes(remotes);
+        let excluded = remotes
+            .iter()
+            .any(|remote| self.excludes.iter().any(|exclude| exclude.matches(&remote.url)));
+        if excluded && candidates.is_empty() {
+            return None;
+        }
+
+        let context = candidates
+            .into_iter()
+            .filter_map(|remote| {
+                let matched = RepoId::parse(&remote.url)?;
+                Some(MatchedRemote {
+                    name: remote.name.clone(),
+                    direction: remote.direction,
+                    url: remote.url.clone(),
+                    raw_url: remote.raw_url.clone(),
+                    via: remote.via.clone(),
+                    renamed_to: None,
+                    role: classify(remote, &matched, primary.as_ref()),
+                    matched,
+                    pattern: 0,
+                })
+            })
+            .collect();
+        Some(context)
+    }
+
+    /// Check the submodule URLs a repository declares against every pattern
+    /// The repository is handed back for the rest of the checks
+    async fn match_submodules(
+        &self,
+        repo: Repository,
+        repo_path: &Path,
+        patterns: &[RepositoryPattern],
+        progress_tx: &Option<mpsc::UnboundedSender<ProgressMessage>>,
+    ) -> Option<(Repository, Vec<MatchedSubmodule>)> {
+        let (repo, submodules) = match git::run_blocking(repo, git::get_submodule_urls).await {
+            Ok((repo, Ok(submodules))) => (repo, submodules),
+            Ok((repo, Err(e))) => {
+                self.warn(
+                    progress_tx,
+                    format!("Warning: Failed to read submodules of {}: {}", repo_path.display(), e),
+                );
+                return Some((repo, Vec::new()));
+            }
+            Err(e) => {
+                self.warn(
+                    progress_tx,
+                    format!("Warning: Failed to read submodules of {}: {}", repo_path.display(), e),
+                );
+                return None;
+            }
+        };
+
+        let mut matching = Vec::new();
+        for mut submodule in submodules {
+            if let Some(url) = self.resolve_url(&submodule.url) {
+                submodule.url = url;
+            }
+
+            let Some(id) = RepoId::parse(&submodule.url) else {
+                continue;
+            };
+            self.seen_repos.lock().await.insert(id);
+
+            if self.excludes.iter().any(|exclude| exclude.matches(&submodule.url)) {
+                continue;
+            }
+            for (idx, pattern) in patterns.iter().enumerate() {
+                if let Some(matched) = pattern.match_remote(&submodule.url) {
+                    matching.push(MatchedSubmodule {
+                        name: submodule.name.clone(),
+                        path: submodule.path.clone(),
+                        url: submodule.url.clone(),
+                        pinned: submodule.pinned.clone(),
+                        matched,
+                        pattern: idx,
+                    });
+                }
+            }
+        }
+
+        Some((repo, matching))
+    }
+
+    /// Stream a match to the progress tracker and add it to the results
+    async fn record_match(
+        &self,
+        match_result: MatchResult,
+        results: &Mutex<Vec<MatchResult>>,
+        progress_tx: &Option<mpsc::UnboundedSender<ProgressMessage>>,
+    ) {
+        // Send progress update for the match
+        if let Some(tx) = progress_tx.as_ref() {
+            let _ = tx.send(ProgressMessage::MatchFound(Box::new(match_result.clone())));
+        }
+
+        // Add to results
+        results.lock().await.push(match_result);
+    }
+
+    /// Main repository to check for a linked worktree found while scanning
+    /// Returns `None` when the main repository is reached by the scan itself,
+    /// was already checked through another worktree, or has disappeared
+    async fn worktree_main(
+        &self,
+        worktree: &Path,
+        link: WorktreeLink,
+        progress_tx: &Option<mpsc::UnboundedSender<ProgressMessage>>,
+    ) -> Option<PathBuf> {
+        if !link.main_exists {
+            self.warn(
+                progress_tx,
+                format!(
+                    "Warning: Worktree {} belongs to missing repository {}",
+                    worktree.display(),
+                    link.main.display()
+                ),
+            );
+            self.orphaned_worktrees.lock().await.push(worktree.to_path_buf());
+            return None;
+        }
+
+        if self.in_search_root(&link.main) {
+            return None;
+        }
+
+        let main = link.main.canonicalize().unwrap_or(link.main);
+        self.checked_mains.lock().await.insert(main.clone()).then_some(main)
+    }
+
+    /// Whether a path lies inside the directory being searched
+    fn in_search_root(&self, path: &Path) -> bool {
+        path.canonicalize().unwrap_or_else(|_| path.to_path_buf()).starts_with(&self.search_root)
+    }
+
+    /// Report a soft failure through the progress tracker, or stderr when verbose
+    fn warn(&self, progress_tx: &Option<mpsc::UnboundedSender<ProgressMessage>>, message: String) {
+        if let Some(tx) = progress_tx.as_ref() {
+            let _ = tx.send(ProgressMessage::Warning(message));
+        } else if self.verbose >= 1 {
+            eprintln!("{}", message);
+        }
+    }
+}
+
+/// Whether a repository is an orphan: every remote it has, if any, is a local path
+/// that no longer exists (existing local remotes were already followed to theirs)
+async fn orphan_of(repo_path: &Path, remotes: &[RemoteUrl]) -> Option<Orphan> {
+    let missing_remotes: Vec<RemoteUrl> = remotes
+        .iter()
+        .filter(|remote| git::local_path(&remote.url, repo_path).is_some_and(|path| !path.exists()))
+        .cloned()
+        .collect();
+    if missing_remotes.len() != remotes.len() {
+        return None;
+    }
+
+    let path = repo_path.to_path_buf();
+    let size = tokio::task::spawn_blocking(move || disk_usage(&path)).await.unwrap_or(0);
+    Some(Orphan { missing_remotes, size })
+}
+
+/// Apparent size of the files in a repository, without following symlinks
+/// Repositories nested in its working tree are left out: they are reported on their own
+fn disk_usage(repo_path: &Path) -> u64 {
+    fn tree_size(path: &Path) -> u64 {
+        let Ok(metadata) = std::fs::symlink_metadata(path) else {
+            return 0;
+        };
+        if !metadata.is_dir() {
+            return metadata.len();
+        }
+        if std::fs::symlink_metadata(path.join(".git")).is_ok() {
+            return 0;
+        }
+
+        std::fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| tree_size(&entry.path())).sum())
+            .unwrap_or(0)
+    }
+
+    std::fs::read_dir(repo_path)
+        .map(|entries| entries.flatten().map(|entry| tree_size(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Nearest repository whose working tree contains `repo_path`
+/// Only the scanned tree is searched: the walk stops at `search_root`
+async fn superproject_of(repo_path: &Path, search_root: &Path) -> Option<Superproject> {
+    let repo_path = repo_path.canonicalize().ok()?;
+    for ancestor in repo_path.ancestors().skip(1).take_while(|ancestor| ancestor.starts_with(search_root)) {
+        if fs::metadata(ancestor.join(".git")).await.is_ok() {
+            let submodule = git::submodule_paths(ancestor).await.contains(&repo_path);
+            return Some(Superproject {
+                path: ancestor.to_path_buf(),
+                submodule,
+            });
+        }
+    }
+    None
+}
+
+/// The remote a repository primarily tracks: "origin", or else the first fetch remote
+/// Returns its name and parsed identity
+fn primary_remote(remotes: &[RemoteUrl]) -> Option<(String, RepoId)> {
+    let fetch_remotes = || remotes.iter().filter(|remote| remote.direction.is_fetch());
+    fetch_remotes()
+        .filter(|remote| remote.name == "origin")
+        .chain(fetch_remotes())
+        .find_map(|remote| Some((remote.name.clone(), RepoId::parse(&remote.url)?)))
+}
+
+/// Role of a repository whose `remote` matched `matched`
+fn classify(remote: &RemoteUrl, matched: &RepoId, primary: Option<&(String, RepoId)>) -> Role {
+    let Some((primary_name, primary_id)) = primary else {
+        return Role::Remote;
+    };
+
+    if remote.name == *primary_name {
+        Role::Clone
+    } else if !primary_id.name.eq_ignore_ascii_case(&matched.name) {
+        Role::Remote
+    } else if primary_id.host == matched.host {
+        if primary_id.namespace.eq_ignore_ascii_case(&matched.namespace) {
+            Role::Clone
+        } else {
+            Role::Fork
+        }
+    } else {
+        Role::Mirror
+    }
 }
 
 // Implement Clone for Scanner to allow spawning tasks
@@ -209,9 +992,417 @@ impl Clone for Scanner {
     fn clone(&self) -> Self {
         Self {
             search_path: self.search_path.clone(),
-            pattern: self.pattern.clone(),
+            patterns: self.patterns.clone(),
+            excludes: self.excludes.clone(),
+            match_on: self.match_on,
+            forks: self.forks,
+            all_worktrees: self.all_worktrees,
+            nested: self.nested,
+            submodules: self.submodules,
+            superprojects: self.superprojects,
+            status: self.status,
+            activity: self.activity,
+            activity_window: self.activity_window,
+            commits: self.commits.clone(),
+            orphans: self.orphans,
+            ref_filter: self.ref_filter.clone(),
+            identity_audit: self.identity_audit,
+            identity_rules: self.identity_rules.clone(),
             max_concurrent: self.max_concurrent,
             verbose: self.verbose,
+            ssh_config: self.ssh_config.clone(),
+            gerrit_hosts: self.gerrit_hosts.clone(),
+            seen_repos: self.seen_repos.clone(),
+            search_root: self.search_root.clone(),
+            checked_mains: self.checked_mains.clone(),
+            orphaned_worktrees: self.orphaned_worktrees.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::tests::{git, remote};
+    use tempfile::TempDir;
+
+    /// Create a repository at `root/name` with the given (name, URL) remotes
+    fn init_repo(root: &Path, name: &str, remotes: &[(&str, &str)]) -> PathBuf {
+        let path = root.join(name);
+        std::fs::create_dir_all(&path).unwrap();
+        git(&path, &["init", "-q"]);
+        for (remote, url) in remotes {
+            git(&path, &["remote", "add", remote, url]);
+        }
+        path
+    }
+
+    fn scanner(root: &Path, patterns: &[&str]) -> Scanner {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| RepositoryPattern::new(pattern).unwrap())
+            .collect();
+        Scanner::new(root.to_path_buf(), patterns, 8, 0, SshConfig::default())
+    }
+
+    /// Paths of the results relative to `root`, sorted
+    fn found(results: &[MatchResult], root: &Path) -> Vec<String> {
+        let mut paths: Vec<String> = results
+            .iter()
+            .map(|result| result.path.strip_prefix(root).unwrap().display().to_string())
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_classify_roles() {
+        let upstream = remote("upstream", "https://github.com/rust-lang/cargo.git");
+        let matched = RepoId::parse(&upstream.url).unwrap();
+        let role_with_origin = |origin_url: &str| {
+            let remotes = vec![remote("origin", origin_url), upstream.clone()];
+            classify(&upstream, &matched, primary_remote(&remotes).as_ref())
+        };
+
+        assert_eq!(role_with_origin("git@github.com:me/cargo.git"), Role::Fork);
+        assert_eq!(role_with_origin("git@gitlab.corp.com:mirrors/cargo.git"), Role::Mirror);
+        assert_eq!(role_with_origin("git@github.com:me/tools.git"), Role::Remote);
+
+        let origin = remote("origin", "git@github.com:rust-lang/cargo.git");
+        let remotes = vec![origin.clone()];
+        assert_eq!(classify(&origin, &matched, primary_remote(&remotes).as_ref()), Role::Clone);
+    }
+
+    #[tokio::test]
+    async fn test_orphans() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_path = temp_dir.path();
+        std::fs::create_dir(repo_path.join("src")).unwrap();
+        std::fs::write(repo_path.join("src/main.rs"), "fn main() {}\n").unwrap();
+
+        let orphan = orphan_of(repo_path, &[]).await.unwrap();
+        assert!(orphan.missing_remotes.is_empty());
+        assert_eq!(orphan.size, 13);
+
+        // A vendored clone counts toward its own size only
+        std::fs::create_dir_all(repo_path.join("vendor/lib/.git")).unwrap();
+        std::fs::write(repo_path.join("vendor/lib/lib.rs"), "pub fn lib() {}\n").unwrap();
+        assert_eq!(orphan_of(repo_path, &[]).await.unwrap().size, 13);
+
+        let gone = remote("origin", "../moved-away");
+        let orphan = orphan_of(repo_path, std::slice::from_ref(&gone)).await.unwrap();
+        assert_eq!(orphan.missing_remotes.len(), 1);
+
+        let backed_up = remote("backup", "git@github.com:me/notes.git");
+        assert!(orphan_of(repo_path, &[gone, backed_up]).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_exclusions_drop_matching_remotes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        init_repo(root, "live", &[("origin", "git@github.com:myorg/live.git")]);
+        init_repo(root, "archived", &[("origin", "git@github.com:myorg/archive-2019.git")]);
+        init_repo(
+            root,
+            "mixed",
+            &[
+                ("origin", "git@github.com:myorg/tools.git"),
+                ("old", "git@github.com:myorg/archive-tools.git"),
+            ],
+        );
+
+        let results = scanner(root, &["myorg/*"]).scan(None).await.unwrap();
+        assert_eq!(found(&results, root), vec!["archived", "live", "mixed"]);
+
+        let results = scanner(root, &["myorg/*"])
+            .with_excludes(vec![RepositoryPattern::new("myorg/archive-*").unwrap()])
+            .scan(None)
+            .await
+            .unwrap();
+        assert_eq!(found(&results, root), vec!["live", "mixed"]);
+
+        let mixed = results.iter().find(|result| result.path.ends_with("mixed")).unwrap();
+        let names: Vec<&str> = mixed.remotes.iter().map(|remote| remote.name.as_str()).collect();
+        assert_eq!(names, vec!["origin"]);
+    }
+
+    #[tokio::test]
+    async fn test_forks_need_a_matching_remote() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        init_repo(
+            root,
+            "fork",
+            &[
+                ("origin", "git@github.com:me/cargo.git"),
+                ("upstream", "https://github.com/rust-lang/cargo.git"),
+            ],
+        );
+        init_repo(root, "unrelated", &[("origin", "git@github.com:other/cargo.git")]);
+
+        let results = scanner(root, &["rust-lang/cargo"]).with_forks(true).scan(None).await.unwrap();
+        assert_eq!(found(&results, root), vec!["fork"]);
+
+        let roles: Vec<(&str, Role)> = results[0]
+            .remotes
+            .iter()
+            .map(|remote| (remote.name.as_str(), remote.role))
+            .collect();
+        assert_eq!(roles, vec![("origin", Role::Fork), ("upstream", Role::Fork)]);
+    }
+
+    #[tokio::test]
+    async fn test_superproject_within_search_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let outer = init_repo(temp_dir.path(), "outer", &[("origin", "git@github.com:org/app.git")]);
+        init_repo(&outer.join("vendor"), "lib", &[("origin", "git@github.com:org/lib.git")]);
+
+        let results = scanner(&outer, &["org/lib"]).with_nested(true).scan(None).await.unwrap();
+        let superproject = results[0].superproject.as_ref().unwrap();
+        assert_eq!(superproject.path, outer.canonicalize().unwrap());
+        assert!(!superproject.submodule);
+
+        let vendor = outer.join("vendor");
+        let results = scanner(&vendor, &["org/lib"]).with_nested(true).scan(None).await.unwrap();
+        assert_eq!(found(&results, &vendor), vec!["lib"]);
+        assert!(results[0].superproject.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_ref_filters_without_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let with_branch = init_repo(root, "with-branch", &[("origin", "git@github.com:org/app.git")]);
+        let without_branch = init_repo(root, "without-branch", &[("origin", "git@github.com:org/lib.git")]);
+        let local_only = init_repo(root, "local-only", &[]);
+        for repo_path in [&with_branch, &without_branch, &local_only] {
+            git(repo_path, &["commit", "--allow-empty", "-m", "init"]);
+        }
+        git(&with_branch, &["branch", "feature/x"]);
+        git(&local_only, &["branch", "feature/x"]);
+
+        let filter = RefFilter {
+            has_branch: Some(glob::Pattern::new("feature/*").unwrap()),
+            ..RefFilter::default()
+        };
+        let results = scanner(root, &[]).with_ref_filter(filter).scan(None).await.unwrap();
+        assert_eq!(found(&results, root), vec!["local-only", "with-branch"]);
+
+        let with_branch = results.iter().find(|result| result.path.ends_with("with-branch")).unwrap();
+        assert_eq!(with_branch.remotes[0].matched.to_string(), "github.com/org/app");
+        assert_eq!(with_branch.refs.as_ref().unwrap().branches, vec!["feature/x"]);
+        assert!(results.iter().any(|result| result.path.ends_with("local-only") && result.remotes.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_identity_audit_without_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let work = init_repo(root, "work", &[("origin", "git@gitlab.corp.com:team/app.git")]);
+        git(&work, &["config", "user.email", "jane@personal.dev"]);
+        init_repo(root, "scratch", &[]);
+
+        let rules = vec![IdentityRule::new("gitlab.corp.com", None, Some("*@corp.com"), None).unwrap()];
+        let results = scanner(root, &[])
+            .with_identity_audit(true)
+            .with_identity_rules(rules)
+            .scan(None)
+            .await
+            .unwrap();
+        assert_eq!(found(&results, root), vec!["scratch", "work"]);
+
+        for result in &results {
+            assert!(result.identity.is_some());
+            let expected = usize::from(result.path.ends_with("work"));
+            assert_eq!(result.identity_violations.len(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_patternless_remotes_are_filtered() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let archived = init_repo(root, "archived", &[("origin", "git@github.com:myorg/archive-x.git")]);
+        let live = init_repo(root, "live", &[("origin", "git@github.com:myorg/live.git")]);
+        git(&live, &["remote", "set-url", "--push", "origin", "git@github.com:me/live.git"]);
+        for repo_path in [&archived, &live] {
+            git(repo_path, &["commit", "--allow-empty", "-m", "init"]);
+            git(repo_path, &["branch", "feature/x"]);
         }
+
+        let filter = || RefFilter {
+            has_branch: Some(glob::Pattern::new("feature/x").unwrap()),
+            ..RefFilter::default()
+        };
+        let results = scanner(root, &[])
+            .with_ref_filter(filter())
+            .with_excludes(vec![RepositoryPattern::new("myorg/archive-*").unwrap()])
+            .scan(None)
+            .await
+            .unwrap();
+        assert_eq!(found(&results, root), vec!["live"]);
+        assert_eq!(results[0].remotes.len(), 2);
+
+        let results = scanner(root, &[])
+            .with_ref_filter(filter())
+            .with_match_on(MatchOn::Push)
+            .scan(None)
+            .await
+            .unwrap();
+        let live = results.iter().find(|result| result.path.ends_with("live")).unwrap();
+        let urls: Vec<&str> = live.remotes.iter().map(|remote| remote.url.as_str()).collect();
+        assert_eq!(urls, vec!["git@github.com:me/live.git"]);
+    }
+
+    #[tokio::test]
+    async fn test_bare_and_mirror_clones() {
+        let source_dir = TempDir::new().unwrap();
+        let source = init_repo(source_dir.path(), "source", &[]);
+        git(&source, &["commit", "--allow-empty", "-m", "init"]);
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for (name, flag) in [("bare.git", "--bare"), ("mirror.git", "--mirror")] {
+            git(root, &["clone", "-q", flag, source.to_str().unwrap(), name]);
+            git(&root.join(name), &["remote", "set-url", "origin", "https://github.com/org/repo.git"]);
+        }
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let results = scanner(root, &["org/repo"]).scan(Some(tx)).await.unwrap();
+        assert_eq!(found(&results, root), vec!["bare.git", "mirror.git"]);
+
+        let result = |name: &str| results.iter().find(|result| result.path.ends_with(name)).unwrap();
+        assert!(result("bare.git").bare && !result("bare.git").mirror);
+        assert!(result("mirror.git").bare && result("mirror.git").mirror);
+
+        // The repository roots are scanned, but nothing inside them
+        let mut scanned = Vec::new();
+        while let Ok(message) = rx.try_recv() {
+            if let ProgressMessage::ScanningDirectory(path) = message {
+                scanned.push(path.strip_prefix(root).unwrap().display().to_string());
+            }
+        }
+        scanned.sort();
+        assert_eq!(scanned, vec!["", "bare.git", "mirror.git"]);
+    }
+
+    /// Paths and branches of a result's linked worktrees
+    fn worktrees(result: &MatchResult) -> Vec<(PathBuf, Option<String>)> {
+        result
+            .worktrees
+            .iter()
+            .map(|worktree| (worktree.path.canonicalize().unwrap(), worktree.branch.clone()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_worktrees_reported_under_main_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let main = init_repo(root, "main", &[("origin", "https://github.com/org/repo.git")]);
+        git(&main, &["commit", "--allow-empty", "-m", "init"]);
+        git(&main, &["worktree", "add", "-q", "-b", "feature", "../feature"]);
+
+        // A worktree in the scanned tree brings in its main repository from outside it
+        let outside_dir = TempDir::new().unwrap();
+        let outside = init_repo(outside_dir.path(), "outside", &[("origin", "https://github.com/org/repo.git")]);
+        git(&outside, &["commit", "--allow-empty", "-m", "init"]);
+        git(&outside, &["worktree", "add", "-q", "-b", "fix", root.join("fix").to_str().unwrap()]);
+
+        let results = scanner(root, &["org/repo"]).scan(None).await.unwrap();
+        assert_eq!(results.len(), 2);
+
+        let main_result = results.iter().find(|result| result.path == main).unwrap();
+        assert_eq!(
+            worktrees(main_result),
+            vec![(root.join("feature").canonicalize().unwrap(), Some("feature".to_string()))]
+        );
+
+        let outside = outside.canonicalize().unwrap();
+        let outside_result = results.iter().find(|result| result.path == outside).unwrap();
+        assert_eq!(
+            worktrees(outside_result),
+            vec![(root.join("fix").canonicalize().unwrap(), Some("fix".to_string()))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_orphaned_worktrees() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let outside_dir = TempDir::new().unwrap();
+        let gone = init_repo(outside_dir.path(), "gone", &[("origin", "https://github.com/org/repo.git")]);
+        git(&gone, &["commit", "--allow-empty", "-m", "init"]);
+        git(&gone, &["worktree", "add", "-q", "-b", "feature", root.join("left-behind").to_str().unwrap()]);
+        std::fs::remove_dir_all(&gone).unwrap();
+
+        let scanner = scanner(root, &["org/repo"]);
+        let results = scanner.scan(None).await.unwrap();
+        assert!(results.is_empty());
+        assert_eq!(scanner.orphaned_worktrees().await, vec![root.join("left-behind")]);
+    }
+
+    /// Create `root/app` with a clone of `lib` committed as the submodule `vendor/lib`;
+    /// the clone's origin and the URL in `.gitmodules` are pointed at github.com/org/lib
+    fn init_superproject(root: &Path, lib: &Path) -> PathBuf {
+        let app = init_repo(root, "app", &[("origin", "https://github.com/org/app.git")]);
+        let lib = lib.to_str().unwrap();
+        git(&app, &["-c", "protocol.file.allow=always", "submodule", "add", "-q", lib, "vendor/lib"]);
+        let url = "https://github.com/org/lib.git";
+        git(&app, &["config", "-f", ".gitmodules", "submodule.vendor/lib.url", url]);
+        git(&app.join("vendor/lib"), &["remote", "set-url", "origin", url]);
+        git(&app, &["commit", "-q", "-am", "add lib"]);
+        app
+    }
+
+    #[tokio::test]
+    async fn test_submodules() {
+        let lib_dir = TempDir::new().unwrap();
+        let lib = init_repo(lib_dir.path(), "lib", &[]);
+        git(&lib, &["commit", "--allow-empty", "-m", "init"]);
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let app = init_superproject(root, &lib);
+
+        // Repositories are not descended into without --submodules
+        let results = scanner(root, &["org/lib"]).scan(None).await.unwrap();
+        assert!(results.is_empty());
+
+        let results = scanner(root, &["org/lib"]).with_submodules(true).scan(None).await.unwrap();
+        assert_eq!(found(&results, root), vec!["app/vendor/lib"]);
+        let superproject = results[0].superproject.as_ref().unwrap();
+        assert_eq!(superproject.path, app.canonicalize().unwrap());
+        assert!(superproject.submodule);
+    }
+
+    #[tokio::test]
+    async fn test_superprojects() {
+        let lib_dir = TempDir::new().unwrap();
+        let lib = init_repo(lib_dir.path(), "lib", &[]);
+        git(&lib, &["commit", "--allow-empty", "-m", "one"]);
+        let pinned = git(&lib, &["rev-parse", "HEAD"]);
+        git(&lib, &["commit", "--allow-empty", "-m", "two"]);
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let app = init_superproject(root, &lib);
+        git(&app.join("vendor/lib"), &["checkout", "-q", &pinned]);
+        git(&app, &["commit", "-q", "-am", "pin lib"]);
+
+        // Only the URLs in .gitmodules are matched, not the superproject's own remotes
+        let results = scanner(root, &["org/app"]).with_superprojects(true).scan(None).await.unwrap();
+        assert!(results.is_empty());
+
+        let results = scanner(root, &["org/lib"]).with_superprojects(true).scan(None).await.unwrap();
+        assert_eq!(found(&results, root), vec!["app"]);
+        assert!(results[0].remotes.is_empty());
+
+        let submodules = &results[0].submodules;
+        assert_eq!(submodules.len(), 1);
+        assert_eq!(submodules[0].path, PathBuf::from("vendor/lib"));
+        assert_eq!(submodules[0].url, "https://github.com/org/lib.git");
+        assert_eq!(submodules[0].pinned.as_deref(), Some(pinned.as_str()));
     }
 }
@@ -0,0 +1,371 @@
+use glob::{MatchOptions, Pattern};
+use std::path::{Path, PathBuf};
+
+/// Host patterns in ssh_config are matched case-insensitively
+const HOST_MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+/// Includes nested deeper than this are ignored (ssh uses the same limit)
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// A `Host` (or `Match`) block and the HostName it sets
+#[derive(Debug, Clone, Default)]
+struct HostBlock {
+    /// Host patterns; a leading `!` negates
+    patterns: Vec<String>,
+    /// Patterns of the blocks enclosing the `Include` this block came from;
+    /// the block only applies to hosts matching all of them too
+    within: Vec<Vec<String>>,
+    hostname: Option<String>,
+}
+
+impl HostBlock {
+    /// Scope of options before the first `Host` line of the top-level config
+    fn every_host() -> Self {
+        Self {
+            patterns: vec!["*".to_string()],
+            ..Self::default()
+        }
+    }
+
+    fn matches(&self, alias: &str) -> bool {
+        self.within.iter().all(|patterns| patterns_match(patterns, alias)) && patterns_match(&self.patterns, alias)
+    }
+
+    /// Scope for `Host` blocks of a file included from within this block
+    fn nested_within(&self) -> Vec<Vec<String>> {
+        let mut within = self.within.clone();
+        within.push(self.patterns.clone());
+        within
+    }
+}
+
+/// A host matches when any positive pattern matches and no negated one does
+fn patterns_match(patterns: &[String], alias: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, pattern.as_str()),
+        };
+
+        let is_match = Pattern::new(pattern).is_ok_and(|p| p.matches_with(alias, HOST_MATCH_OPTIONS));
+        if is_match && negated {
+            return false;
+        }
+        matched |= is_match;
+    }
+    matched
+}
+
+/// `Host` alias to `HostName` mapping from the user's OpenSSH config
+#[derive(Debug, Default)]
+pub struct SshConfig {
+    blocks: Vec<HostBlock>,
+}
+
+impl SshConfig {
+    /// Load `~/.ssh/config`, following `Include` directives
+    /// A missing or unreadable config yields an empty mapping
+    pub fn load() -> Self {
+        match dirs::home_dir() {
+            Some(home) => Self::from_file(&home.join(".ssh").join("config")),
+            None => Self::default(),
+        }
+    }
+
+    /// Load a config file; relative `Include` paths resolve against its directory
+    pub fn from_file(path: &Path) -> Self {
+        let mut config = Self::default();
+        let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        config.include_file(path, &base_dir, 0, &HostBlock::every_host());
+        config
+    }
+
+    /// Parse config text; relative `Include` paths resolve against `base_dir`
+    pub fn parse(contents: &str, base_dir: &Path) -> Self {
+        let mut config = Self::default();
+        config.parse_into(contents, base_dir, 0, &HostBlock::every_host());
+        config
+    }
+
+    /// Real hostname for an alias, if a matching block sets `HostName`
+    /// Like ssh, the first block that provides a value wins
+    pub fn hostname(&self, alias: &str) -> Option<String> {
+        self.blocks
+            .iter()
+            .filter(|block| block.matches(alias))
+            .find_map(|block| block.hostname.as_deref())
+            .map(|hostname| hostname.replace("%h", alias))
+    }
+
+    /// Rewrite an SSH remote URL so its host is the real hostname
+    /// Handles scp-like (`git@alias:org/repo.git`) and `ssh://` URLs;
+    /// returns `None` when the URL is not SSH or the host is not an alias
+    pub fn resolve_url(&self, url: &str) -> Option<String> {
+        let (prefix, authority, rest) = split_ssh_url(url)?;
+
+        let (user, host_port) = match authority.rsplit_once('@') {
+            Some((user, host_port)) => (Some(user), host_port),
+            None => (None, authority),
+        };
+
+        // ssh:// URLs may carry a port after the host
+        let (host, port) = match host_port.split_once(':') {
+            Some((host, port)) if prefix.is_some() => (host, Some(port)),
+            _ => (host_port, None),
+        };
+
+        let hostname = self.hostname(host).filter(|hostname| !hostname.eq_ignore_ascii_case(host))?;
+
+        let mut resolved = prefix.unwrap_or_default().to_string();
+        if let Some(user) = user {
+            resolved.push_str(user);
+            resolved.push('@');
+        }
+        resolved.push_str(&hostname);
+        if let Some(port) = port {
+            resolved.push(':');
+            resolved.push_str(port);
+        }
+        resolved.push_str(rest);
+
+        Some(resolved)
+    }
+
+    fn include_file(&mut self, path: &Path, base_dir: &Path, depth: usize, scope: &HostBlock) {
+        if let Ok(contents) = std::fs::read_to_string(path) {
+            self.parse_into(&contents, base_dir, depth, scope);
+        }
+    }
+
+    /// Parse config text whose options apply within `scope`: every host at the
+    /// top level, or the block an `Include` appeared in
+    fn parse_into(&mut self, contents: &str, base_dir: &Path, depth: usize, scope: &HostBlock) {
+        let within = scope.nested_within();
+        // Block that options currently apply to, and its index once pushed
+        let mut block = scope.clone();
+        let mut current: Option<usize> = None;
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            // "Keyword value" or "Keyword=value"
+            let (keyword, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+                Some((keyword, value)) => (keyword, value.trim_start_matches([' ', '\t', '=']).trim()),
+                None => (line, ""),
+            };
+
+            match keyword.to_ascii_lowercase().as_str() {
+                // Match criteria are not evaluated; such blocks (no patterns) never apply
+                keyword @ ("host" | "match") => {
+                    block = HostBlock {
+                        patterns: if keyword == "host" { split_args(value) } else { Vec::new() },
+                        within: within.clone(),
+                        hostname: None,
+                    };
+                    self.blocks.push(block.clone());
+                    current = Some(self.blocks.len() - 1);
+                }
+                "hostname" => {
+                    let idx = *current.get_or_insert_with(|| {
+                        self.blocks.push(block.clone());
+                        self.blocks.len() - 1
+                    });
+                    let entry = &mut self.blocks[idx];
+                    if entry.hostname.is_none() {
+                        entry.hostname = split_args(value).into_iter().next();
+                    }
+                }
+                "include" if depth < MAX_INCLUDE_DEPTH => {
+                    for arg in split_args(value) {
+                        for path in expand_include(&arg, base_dir) {
+                            self.include_file(&path, base_dir, depth + 1, &block);
+                        }
+                    }
+                    // Later options of this block go after the included blocks
+                    current = None;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Split an SSH URL into (scheme prefix, authority, rest of URL)
+/// `ssh://git@alias:22/org/repo` -> (Some("ssh://"), "git@alias:22", "/org/repo")
+/// `git@alias:org/repo` -> (None, "git@alias", ":org/repo")
+fn split_ssh_url(url: &str) -> Option<(Option<&str>, &str, &str)> {
+    if let Some((scheme, after)) = url.split_once("://") {
+        if !matches!(scheme, "ssh" | "git+ssh" | "ssh+git") {
+            return None;
+        }
+        let prefix = &url[..scheme.len() + 3];
+        let end = after.find('/').unwrap_or(after.len());
+        return Some((Some(prefix), &after[..end], &after[end..]));
+    }
+
+    // scp-like syntax: the colon must come before any slash
+    let colon = url.find(':')?;
+    if url[..colon].contains('/') || colon == 0 {
+        return None;
+    }
+    Some((None, &url[..colon], &url[colon..]))
+}
+
+/// Split whitespace-separated arguments, honoring double quotes
+fn split_args(value: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in value.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+
+    args
+}
+
+/// Expand `~` and globs in an Include path; relative paths resolve against `base_dir`
+fn expand_include(arg: &str, base_dir: &Path) -> Vec<PathBuf> {
+    let path = match arg.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        None => base_dir.join(arg),
+    };
+
+    match glob::glob(&path.to_string_lossy()) {
+        Ok(paths) => {
+            let mut paths: Vec<PathBuf> = paths.flatten().collect();
+            paths.sort();
+            paths
+        }
+        Err(_) => vec![path],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CONFIG: &str = "
+# Work account
+Host github-work
+    HostName github.com
+    User git
+    IdentityFile ~/.ssh/id_work
+
+Host gl-* !gl-skip
+  HostName=gitlab.example.com
+
+Host *.internal
+    HostName %h.corp.com
+
+Host *
+    ServerAliveInterval 60
+";
+
+    #[test]
+    fn test_hostname_lookup() {
+        let config = SshConfig::parse(CONFIG, Path::new("/nonexistent"));
+
+        assert_eq!(config.hostname("github-work").as_deref(), Some("github.com"));
+        assert_eq!(config.hostname("GitHub-Work").as_deref(), Some("github.com"));
+        assert_eq!(config.hostname("gl-team").as_deref(), Some("gitlab.example.com"));
+        assert_eq!(config.hostname("gl-skip"), None);
+        assert_eq!(config.hostname("git.internal").as_deref(), Some("git.internal.corp.com"));
+        assert_eq!(config.hostname("github.com"), None);
+    }
+
+    #[test]
+    fn test_resolve_url() {
+        let config = SshConfig::parse(CONFIG, Path::new("/nonexistent"));
+
+        assert_eq!(
+            config.resolve_url("git@github-work:org/repo.git").as_deref(),
+            Some("git@github.com:org/repo.git")
+        );
+        assert_eq!(
+            config.resolve_url("github-work:org/repo.git").as_deref(),
+            Some("github.com:org/repo.git")
+        );
+        assert_eq!(
+            config.resolve_url("ssh://git@gl-team:2222/group/sub/repo.git").as_deref(),
+            Some("ssh://git@gitlab.example.com:2222/group/sub/repo.git")
+        );
+
+        // Not SSH, or not an alias
+        assert_eq!(config.resolve_url("https://github-work/org/repo.git"), None);
+        assert_eq!(config.resolve_url("git@github.com:org/repo.git"), None);
+        assert_eq!(config.resolve_url("/srv/git/repo.git"), None);
+    }
+
+    #[test]
+    fn test_include_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let conf_d = temp_dir.path().join("conf.d");
+        std::fs::create_dir(&conf_d).unwrap();
+
+        std::fs::write(conf_d.join("10-work.conf"), "Host work\n  HostName gitlab.corp.com\n").unwrap();
+        std::fs::write(conf_d.join("20-home.conf"), "Host home\n  HostName github.com\n").unwrap();
+        std::fs::write(
+            temp_dir.path().join("config"),
+            "Include conf.d/*.conf\n\nHost work\n  HostName ignored.example.com\n",
+        )
+        .unwrap();
+
+        let config = SshConfig::from_file(&temp_dir.path().join("config"));
+
+        // The included block comes first, so its HostName wins
+        assert_eq!(config.hostname("work").as_deref(), Some("gitlab.corp.com"));
+        assert_eq!(config.hostname("home").as_deref(), Some("github.com"));
+    }
+
+    #[test]
+    fn test_include_inside_host_block() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("defaults.conf"), "HostName gitlab.corp.com\n").unwrap();
+        std::fs::write(
+            temp_dir.path().join("internal.conf"),
+            "Host *.internal\n  HostName %h.corp.com\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("config"),
+            "Host work\n  Include defaults.conf\n\nHost home\n  Include internal.conf\n  HostName github.com\n",
+        )
+        .unwrap();
+
+        let config = SshConfig::from_file(&temp_dir.path().join("config"));
+
+        // Options of an included file stay within the block that included it
+        assert_eq!(config.hostname("work").as_deref(), Some("gitlab.corp.com"));
+        assert_eq!(config.hostname("other"), None);
+        assert_eq!(config.hostname("git.internal"), None);
+        // HostName after the Include still belongs to "Host home"
+        assert_eq!(config.hostname("home").as_deref(), Some("github.com"));
+    }
+
+    #[test]
+    fn test_missing_config_is_empty() {
+        let config = SshConfig::from_file(Path::new("/nonexistent/ssh/config"));
+        assert_eq!(config.hostname("anything"), None);
+    }
+}
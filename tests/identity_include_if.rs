@@ -0,0 +1,55 @@
+//! Global-config identity resolution, in a binary of its own: pointing libgit2 at
+//! a test home directory changes the search path for the whole process
+
+use fsgitwatch::git::get_identity;
+use git2::{ConfigLevel, Repository};
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn git(repo_path: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+        .args(args)
+        .current_dir(repo_path)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+#[test]
+fn test_identity_with_include_if() {
+    let repo = TempDir::new().unwrap();
+    let repo_path = repo.path();
+    git(repo_path, &["init"]);
+    git(repo_path, &["remote", "add", "origin", "https://gitlab.corp.com/team/app.git"]);
+    git(repo_path, &["commit", "--allow-empty", "-m", "initial"]);
+
+    // A home directory whose ~/.gitconfig picks the identity, as a user's would
+    let home = TempDir::new().unwrap();
+    std::fs::write(
+        home.path().join("work.gitconfig"),
+        "[user]\n\tname = Jane Doe\n\temail = jane@corp.com\n",
+    )
+    .unwrap();
+    std::fs::write(home.path().join("release.gitconfig"), "[user]\n\temail = release@corp.com\n").unwrap();
+    // Like "gitdir:~/work/": every repository below the directory
+    let global_config = format!(
+        "[includeIf \"gitdir:{}/\"]\n\tpath = work.gitconfig\n\
+         [includeIf \"onbranch:release/*\"]\n\tpath = release.gitconfig\n",
+        repo_path.canonicalize().unwrap().display()
+    );
+    std::fs::write(home.path().join(".gitconfig"), global_config).unwrap();
+
+    // SAFETY: this is the only test in the binary, so nothing reads config concurrently
+    unsafe { git2::opts::set_search_path(ConfigLevel::Global, home.path()).unwrap() };
+
+    let identity = get_identity(&Repository::open(repo_path).unwrap()).unwrap();
+    assert_eq!(identity.name.as_deref(), Some("Jane Doe"));
+    assert_eq!(identity.email.as_deref(), Some("jane@corp.com"));
+
+    git(repo_path, &["checkout", "-b", "release/2.1"]);
+    let release_identity = get_identity(&Repository::open(repo_path).unwrap()).unwrap();
+    assert_eq!(release_identity.name.as_deref(), Some("Jane Doe"));
+    assert_eq!(release_identity.email.as_deref(), Some("release@corp.com"));
+}